@@ -8,10 +8,14 @@
 
 /// I'm a doc comment on the type.
 #[repr(transparent)]
-#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct CoolIndex {
     _raw: u32,
 }
+const _: () = assert!(
+    core::mem::size_of::<u32>() <= core::mem::size_of::<usize>(),
+    "define_index_type!: the raw type can't be wider than `usize`",
+);
 impl CoolIndex {
     /// If `Self::CHECKS_MAX_INDEX` is true, we'll assert if trying to
     /// produce a value larger than this in any of the ctors that don't
@@ -20,6 +24,10 @@ impl CoolIndex {
     /// Does this index type assert if asked to construct an index
     /// larger than MAX_INDEX?
     pub const CHECKS_MAX_INDEX: bool = !false;
+    /// The index with the value 0.
+    pub const ZERO: Self = Self::from_usize_unchecked(0);
+    /// The largest valid index, e.g. the one with the value `MAX_INDEX`.
+    pub const MAX: Self = Self::from_usize_unchecked(Self::MAX_INDEX);
     /// Construct this index type from a usize. Alias for `from_usize`.
     #[inline(always)]
     pub fn new(value: usize) -> Self {
@@ -51,7 +59,19 @@ impl CoolIndex {
         Self::check_index(value as usize);
         Self { _raw: value as u32 }
     }
-    /// Get the wrapped index as a usize.
+    /// Construct this index type from a usize, returning `None` where
+    /// `from_usize` would panic.
+    #[inline]
+    pub fn try_from_usize(value: usize) -> Option<Self> {
+        if Self::CHECKS_MAX_INDEX && (value > Self::MAX_INDEX) {
+            None
+        } else {
+            Some(Self { _raw: value as u32 })
+        }
+    }
+    /// Get the wrapped index as a usize. Unlike `Idx::index`, this can
+    /// be used in `const` contexts (and it takes priority over the
+    /// trait method, so `i.index()` always works there).
     #[inline(always)]
     pub const fn index(self) -> usize {
         self._raw as usize
@@ -68,6 +88,41 @@ impl CoolIndex {
             crate::__max_check_fail(v, Self::MAX_INDEX);
         }
     }
+    /// Returns `self + rhs`, or `None` if that would go past
+    /// `MAX_INDEX` (or the raw type's max, if checks are disabled).
+    #[inline]
+    pub fn checked_add(self, rhs: usize) -> Option<Self> {
+        let v = self.index().checked_add(rhs)?;
+        if v > Self::ARITHMETIC_MAX {
+            None
+        } else {
+            Some(Self { _raw: v as u32 })
+        }
+    }
+    /// Returns `self - rhs`, or `None` if that would go below zero.
+    #[inline]
+    pub fn checked_sub(self, rhs: usize) -> Option<Self> {
+        let v = self.index().checked_sub(rhs)?;
+        Some(Self { _raw: v as u32 })
+    }
+    /// Returns `self + rhs`, clamped to `MAX_INDEX` (or the raw type's max,
+    /// if checks are disabled).
+    #[inline]
+    pub fn saturating_add(self, rhs: usize) -> Self {
+        let v = self.index().saturating_add(rhs).min(Self::ARITHMETIC_MAX);
+        Self { _raw: v as u32 }
+    }
+    /// Returns `self - rhs`, clamped to zero.
+    #[inline]
+    pub fn saturating_sub(self, rhs: usize) -> Self {
+        Self { _raw: self.index().saturating_sub(rhs) as u32 }
+    }
+    // The largest value `checked_add` and `saturating_add` will produce.
+    const ARITHMETIC_MAX: usize = if Self::CHECKS_MAX_INDEX {
+        Self::MAX_INDEX
+    } else {
+        <u32>::MAX as usize
+    };
     const _ENSURE_RAW_IS_UNSIGNED: [(); 0] = [(); <u32>::MIN as usize];
 }
 impl core::fmt::Debug for CoolIndex {
@@ -75,6 +130,24 @@ impl core::fmt::Debug for CoolIndex {
         f.write_fmt(format_args!("CI({0:?})", self.index()))
     }
 }
+impl core::hash::Hash for CoolIndex {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index().hash(state)
+    }
+}
+impl core::cmp::Ord for CoolIndex {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.index().cmp(&other.index())
+    }
+}
+impl core::cmp::PartialOrd for CoolIndex {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
 impl core::cmp::PartialOrd<usize> for CoolIndex {
     #[inline]
     fn partial_cmp(&self, other: &usize) -> Option<core::cmp::Ordering> {
@@ -146,40 +219,18 @@ impl core::ops::Sub<CoolIndex> for usize {
         CoolIndex::new(self.wrapping_sub(other.index()))
     }
 }
-impl core::ops::Add for CoolIndex {
-    type Output = CoolIndex;
-    #[inline]
-    fn add(self, other: CoolIndex) -> CoolIndex {
-        CoolIndex::new(other.index() + self.index())
-    }
-}
-impl core::ops::Sub for CoolIndex {
-    type Output = CoolIndex;
-    #[inline]
-    fn sub(self, other: CoolIndex) -> CoolIndex {
-        CoolIndex::new(self.index().wrapping_sub(other.index()))
-    }
-}
-impl core::ops::AddAssign for CoolIndex {
-    #[inline]
-    fn add_assign(&mut self, other: CoolIndex) {
-        *self = *self + other;
-    }
-}
-impl core::ops::SubAssign for CoolIndex {
-    #[inline]
-    fn sub_assign(&mut self, other: CoolIndex) {
-        *self = *self - other;
-    }
-}
 impl crate::Idx for CoolIndex {
     #[inline]
     fn from_usize(value: usize) -> Self {
-        Self::from(value)
+        CoolIndex::from_usize(value)
+    }
+    #[inline]
+    fn try_from_usize(value: usize) -> Option<Self> {
+        CoolIndex::try_from_usize(value)
     }
     #[inline]
     fn index(self) -> usize {
-        usize::from(self)
+        CoolIndex::index(self)
     }
 }
 impl From<CoolIndex> for usize {
@@ -50,11 +50,32 @@ impl<I: Idx, T: fmt::Debug + ?Sized> fmt::Debug for IndexSlice<I, T> {
         fmt::Debug::fmt(&self.raw, fmt)
     }
 }
+
+// Returned by `IndexSlice::debug_sparse`, to print a slice minus its sentinels.
+struct DebugSparse<'a, I: Idx, T, F> {
+    slice: &'a IndexSlice<I, [T]>,
+    is_sentinel: F,
+}
+
+impl<'a, I: Idx, T: fmt::Debug, F: Fn(&T) -> bool> fmt::Debug for DebugSparse<'a, I, T, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map()
+            .entries(
+                self.slice
+                    .iter_enumerated()
+                    .filter(|(_, t)| !(self.is_sentinel)(t)),
+            )
+            .finish()
+    }
+}
+
 /// `IndexBox<I, [T]>`: An alias for indexed boxed slice.
 pub type IndexBox<I, T> = Box<IndexSlice<I, T>>;
 
+type Indices<I> = iter::Map<Range<usize>, fn(usize) -> I>;
 type SliceMapped<Iter, I, T> = iter::Map<Iter, fn(&[T]) -> &IndexSlice<I, [T]>>;
 type SliceMappedMut<Iter, I, T> = iter::Map<Iter, fn(&mut [T]) -> &mut IndexSlice<I, [T]>>;
+type RangeEnumerated<'a, I, T> = iter::Zip<Indices<I>, slice::Iter<'a, T>>;
 
 impl<I: Idx, T> IndexSlice<I, [T]> {
     /// Construct a new IdxSlice by wrapping an existing slice.
@@ -90,6 +111,23 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         IndexVec::from_vec(self.raw.to_vec())
     }
 
+    /// Borrows `self` as a slice with a different index type.
+    ///
+    /// Panics if our length is too large for `J`.
+    #[inline]
+    pub fn as_reindexed<J: Idx>(&self) -> &IndexSlice<J, [T]> {
+        // See if `J::from_usize` might be upset by this length.
+        let _ = J::from_usize(self.len());
+        IndexSlice::new(&self.raw)
+    }
+
+    /// Wraps a boxed slice without clones or allocation. This is the same as
+    /// `b.into()`, but doesn't need a type annotation on the result.
+    #[inline]
+    pub fn from_boxed_slice(b: Box<[T]>) -> Box<Self> {
+        unsafe { Box::from_raw(Box::into_raw(b) as *mut Self) }
+    }
+
     /// Converts `self` into a vector without clones or allocation.
     ///
     /// The resulting vector can be converted back into a box via
@@ -111,12 +149,33 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         &mut self.raw
     }
 
+    /// Returns a slice of `Cell`s over our items, so that they can be mutated
+    /// through shared references while keeping typed indexing. This is the
+    /// equivalent of `Cell::from_mut(slice).as_slice_of_cells()`.
+    #[inline]
+    pub fn as_slice_of_cells(&mut self) -> &IndexSlice<I, [core::cell::Cell<T>]> {
+        IndexSlice::new(core::cell::Cell::from_mut(&mut self.raw).as_slice_of_cells())
+    }
+
     /// Returns the underlying slice.
     #[inline(always)]
     pub const fn as_raw_slice(&self) -> &[T] {
         &self.raw
     }
 
+    /// Returns the bytes of our items, without copying. Requires the
+    /// `bytemuck` feature.
+    ///
+    /// Note that multi-byte items are in native byte order.
+    #[cfg(feature = "bytemuck")]
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8]
+    where
+        T: bytemuck::Pod,
+    {
+        bytemuck::cast_slice(&self.raw)
+    }
+
     /// Returns an unsafe mutable pointer to the slice's buffer.
     #[inline]
     pub fn as_mut_ptr(&mut self) -> *mut T {
@@ -129,6 +188,50 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         self.raw.as_ptr()
     }
 
+    /// Recovers the index of `elem`, given a reference to one of our items.
+    /// Returns `None` if `elem` doesn't point into this slice.
+    ///
+    /// For zero-sized `T` every item has the same address, so this always
+    /// returns `None`.
+    #[inline]
+    pub fn index_of_ref(&self, elem: &T) -> Option<I> {
+        let size = core::mem::size_of::<T>();
+        if size == 0 {
+            return None;
+        }
+        let start = self.raw.as_ptr() as usize;
+        let offset = (elem as *const T as usize).checked_sub(start)?;
+        let i = offset / size;
+        if offset % size == 0 && i < self.len() {
+            Some(I::from_usize(i))
+        } else {
+            None
+        }
+    }
+
+    /// Return the index of the first element, or panic.
+    #[inline]
+    pub fn first_idx(&self) -> I {
+        assert!(!self.is_empty());
+        I::from_usize(0)
+    }
+
+    /// Return the index of the first element, or `None` if we're empty.
+    #[inline]
+    pub fn get_first_idx(&self) -> Option<I> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(I::from_usize(0))
+        }
+    }
+
+    /// Return the index of the last element, or `None` if we're empty.
+    #[inline]
+    pub fn get_last_idx(&self) -> Option<I> {
+        self.len().checked_sub(1).map(I::from_usize)
+    }
+
     /// Return the index of the last element, or panic.
     #[inline]
     pub fn last_idx(&self) -> I {
@@ -138,12 +241,40 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         I::from_usize(self.len() - 1)
     }
 
+    /// Returns true if every index in `indices` is in bounds for us, e.g. to
+    /// validate a batch of indices from an untrusted source before use.
+    #[inline]
+    pub fn all_in_bounds<It: IntoIterator<Item = I>>(&self, indices: It) -> bool {
+        let len = self.len();
+        indices.into_iter().all(|i| i.index() < len)
+    }
+
+    /// Returns the first and last valid indices (both inclusive), or `None` if
+    /// we're empty.
+    #[inline]
+    pub fn bounds(&self) -> Option<(I, I)> {
+        let last = self.len().checked_sub(1)?;
+        Some((I::from_usize(0), I::from_usize(last)))
+    }
+
+    /// Returns the full range of valid indices, e.g. `0..self.len_idx()`.
+    #[inline]
+    pub fn index_range(&self) -> Range<I> {
+        I::from_usize(0)..I::from_usize(self.len())
+    }
+
     /// Returns the length of our slice.
     #[inline]
     pub const fn len(&self) -> usize {
         self.raw.len()
     }
 
+    /// Returns the length of our slice as a [`Len`].
+    #[inline]
+    pub fn typed_len(&self) -> Len<I> {
+        Len::new(self.len())
+    }
+
     /// Returns the length of our slice as an `I`.
     #[inline]
     pub fn len_idx(&self) -> I {
@@ -184,12 +315,157 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
             .map(|(i, t)| (I::from_usize(i), t))
     }
 
+    /// Shorthand for `self.iter_enumerated().peekable()`, which lets you look
+    /// at the next index and item without consuming them.
+    ///
+    /// ```
+    /// # use index_vec::{index_vec, IndexVec};
+    /// index_vec::define_index_type! { struct Ix = u32; }
+    /// # fn main() {
+    /// let v: IndexVec<Ix, char> = index_vec!['a', 'b'];
+    /// let mut it = v.peeking_indices();
+    /// assert_eq!(it.peek(), Some(&(Ix::new(0), &'a')));
+    /// assert_eq!(it.next(), Some((Ix::new(0), &'a')));
+    /// assert_eq!(it.peek().map(|&(i, _)| i), Some(Ix::new(1)));
+    /// # }
+    /// ```
+    #[inline]
+    pub fn peeking_indices(&self) -> iter::Peekable<Enumerated<slice::Iter<'_, T>, I, &T>> {
+        self.iter_enumerated().peekable()
+    }
+
+    /// Similar to `self.iter_enumerated()`, but only over the items in `range`.
+    ///
+    /// Unlike `self[range].iter_enumerated()`, the indices produced are
+    /// relative to the start of `self`, not to the start of `range`.
+    #[inline]
+    pub fn enumerated_range<R: IdxRangeBounds<I>>(&self, range: R) -> RangeEnumerated<'_, I, T> {
+        use core::ops::{Bound, RangeBounds};
+        let range = range.into_range();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let items = &self.raw[(range.start_bound().cloned(), range.end_bound().cloned())];
+        (start..start + items.len())
+            .map(I::from_usize as fn(usize) -> I)
+            .zip(items.iter())
+    }
+
+    /// Iterates over our items together with the items of `other` (which must
+    /// be the same length), along with their shared index.
+    ///
+    /// Unlike `Iterator::zip`, a length mismatch is treated as a bug, and
+    /// triggers a debug assertion instead of silently truncating.
+    #[inline]
+    pub fn iter_enumerated_zip<'a, U>(
+        &'a self,
+        other: &'a IndexSlice<I, [U]>,
+    ) -> impl Iterator<Item = (I, &'a T, &'a U)> + 'a {
+        debug_assert_eq!(
+            self.len(),
+            other.len(),
+            "iter_enumerated_zip: length mismatch"
+        );
+        self.iter_enumerated()
+            .zip(other.raw.iter())
+            .map(|((i, t), u)| (i, t, u))
+    }
+
+    /// The mutable version of [`IndexSlice::iter_enumerated_zip`].
+    #[inline]
+    pub fn iter_enumerated_zip_mut<'a, U>(
+        &'a mut self,
+        other: &'a mut IndexSlice<I, [U]>,
+    ) -> impl Iterator<Item = (I, &'a mut T, &'a mut U)> + 'a {
+        debug_assert_eq!(
+            self.len(),
+            other.len(),
+            "iter_enumerated_zip_mut: length mismatch"
+        );
+        self.iter_mut_enumerated()
+            .zip(other.raw.iter_mut())
+            .map(|((i, t), u)| (i, t, u))
+    }
+
+    /// Iterates over the sub-items of each item (as given by `f`), paired with
+    /// the index of the item they came from.
+    #[inline]
+    pub fn flat_enumerated<'a, U: 'a, F: Fn(&'a T) -> &'a [U] + 'a>(
+        &'a self,
+        f: F,
+    ) -> impl Iterator<Item = (I, &'a U)> + 'a {
+        self.iter_enumerated()
+            .flat_map(move |(i, t)| f(t).iter().map(move |u| (i, u)))
+    }
+
+    /// Builds a new vector by cloning `self[indices[j]]` for each `j`, e.g. to
+    /// apply a permutation or selection computed elsewhere.
+    ///
+    /// Panics if any of `indices` are out of bounds.
+    #[inline]
+    pub fn gather<J: Idx>(&self, indices: &IndexSlice<J, [I]>) -> IndexVec<J, T>
+    where
+        T: Clone,
+    {
+        indices.iter().map(|&i| self[i].clone()).collect()
+    }
+
+    /// Like `self.iter_enumerated().find_map(...)`, calling `f` with each index
+    /// and item, and returning the first non-`None` result.
+    #[inline]
+    pub fn find_map_enumerated<R, F: FnMut(I, &T) -> Option<R>>(&self, mut f: F) -> Option<R> {
+        self.iter_enumerated().find_map(|(i, t)| f(i, t))
+    }
+
+    /// Builds a new `IndexVec` with the same indices as `self`, by calling `f`
+    /// with each index and item.
+    #[inline]
+    pub fn map_collect<U, F: FnMut(I, &T) -> U>(&self, mut f: F) -> IndexVec<I, U> {
+        self.iter_enumerated().map(|(i, t)| f(i, t)).collect()
+    }
+
+    /// Calls `f` with each index and item in order, stopping at (and returning)
+    /// the first error.
+    #[inline]
+    pub fn try_for_each_enumerated<E, F: FnMut(I, &T) -> Result<(), E>>(
+        &self,
+        mut f: F,
+    ) -> Result<(), E> {
+        self.iter_enumerated().try_for_each(|(i, t)| f(i, t))
+    }
+
     /// Get an interator over all our indices.
     #[inline(always)]
-    pub fn indices(&self) -> iter::Map<Range<usize>, fn(usize) -> I> {
+    pub fn indices(&self) -> Indices<I> {
         (0..self.raw.len()).map(I::from_usize)
     }
 
+    /// Returns an adapter whose `Debug` impl prints us as a map from index to
+    /// item, skipping the items for which `is_sentinel` returns true, e.g.
+    /// `{0: 'a', 2: 'c'}`.
+    #[inline]
+    pub fn debug_sparse<'a, F: Fn(&T) -> bool + 'a>(
+        &'a self,
+        is_sentinel: F,
+    ) -> impl fmt::Debug + 'a
+    where
+        T: fmt::Debug,
+    {
+        DebugSparse {
+            slice: self,
+            is_sentinel,
+        }
+    }
+
+    /// Returns `self.indices()` and `self.iter()` as a pair of aligned
+    /// iterators, which can be zipped back together or used separately.
+    #[inline]
+    pub fn indices_and_values(&self) -> (Indices<I>, slice::Iter<'_, T>) {
+        (self.indices(), self.raw.iter())
+    }
+
     /// Similar to `self.iter_mut().enumerate()` but with indices of `I` and not
     /// `usize`.
     #[inline(always)]
@@ -266,6 +542,30 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         self.raw.starts_with(needle.as_ref())
     }
 
+    /// Forwards to the slice's `strip_prefix` implementation.
+    ///
+    /// Note that indices into the result are relative to the end of the
+    /// prefix, not to the start of `self`.
+    #[inline]
+    pub fn strip_prefix<S: AsRef<[T]> + ?Sized>(&self, prefix: &S) -> Option<&Self>
+    where
+        T: PartialEq,
+    {
+        self.raw.strip_prefix(prefix.as_ref()).map(Self::from_slice)
+    }
+
+    /// Forwards to the slice's `strip_suffix` implementation.
+    ///
+    /// The result starts where `self` does, so indices into it are the same as
+    /// indices into `self`.
+    #[inline]
+    pub fn strip_suffix<S: AsRef<[T]> + ?Sized>(&self, suffix: &S) -> Option<&Self>
+    where
+        T: PartialEq,
+    {
+        self.raw.strip_suffix(suffix.as_ref()).map(Self::from_slice)
+    }
+
     /// Forwards to the slice's `contains` implementation.
     #[inline]
     pub fn contains(&self, x: &T) -> bool
@@ -344,6 +644,106 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
             Err(i) => Err(I::from_usize(i)),
         }
     }
+
+    /// Returns the range of indices of every item in this sorted slice whose
+    /// key (as given by `f`) is equal to `b`. This is like C++'s
+    /// `std::equal_range`.
+    ///
+    /// If there are no such items, the range is empty, and starts at the index
+    /// where an item with that key could be inserted.
+    pub fn equal_range_by_key<B: Ord, F: FnMut(&T) -> B>(&self, b: &B, mut f: F) -> Range<I> {
+        let lower = self.raw.partition_point(|x| f(x) < *b);
+        let upper = lower + self.raw[lower..].partition_point(|x| f(x) <= *b);
+        I::from_usize(lower)..I::from_usize(upper)
+    }
+
+    /// Like [`IndexSlice::binary_search`], but starts by galloping outwards
+    /// from `hint`, which makes it faster when `value` is likely to be near
+    /// `hint` (e.g. for lookups that are close to the previous one).
+    ///
+    /// As with `binary_search`, if there are several matches, any one of them
+    /// may be returned.
+    pub fn search_hinted(&self, value: &T, hint: I) -> Result<I, I>
+    where
+        T: Ord,
+    {
+        let raw = &self.raw;
+        let len = raw.len();
+        let hint = hint.index().min(len);
+        // Find a window `lo..hi` of `raw`, such that everything before it is
+        // less than `value`, and (unless it ends at `len`) its last item isn't.
+        let (mut lo, mut hi) = (0, len);
+        let mut step = 1;
+        if hint < len && raw[hint] < *value {
+            lo = hint + 1;
+            while hint + step < len {
+                if raw[hint + step] < *value {
+                    lo = hint + step + 1;
+                    step *= 2;
+                } else {
+                    hi = hint + step + 1;
+                    break;
+                }
+            }
+        } else {
+            hi = (hint + 1).min(len);
+            while step <= hint {
+                if raw[hint - step] < *value {
+                    lo = hint - step + 1;
+                    break;
+                } else {
+                    hi = hint - step + 1;
+                    step *= 2;
+                }
+            }
+        }
+        match raw[lo..hi].binary_search(value) {
+            Ok(i) => Ok(I::from_usize(lo + i)),
+            Err(i) => Err(I::from_usize(lo + i)),
+        }
+    }
+
+    /// Searches this sorted slice for the item nearest to `value`, returning
+    /// its index, or `None` if we're empty.
+    ///
+    /// `distance` should return how far an item is from `value`. When `value`
+    /// falls between two items, it's used to pick the closer of the two (the
+    /// earlier one on a tie). Values past either end are clamped to the first
+    /// or last index.
+    pub fn nearest_by_key<D: Ord, F: FnMut(&T) -> D>(&self, value: &T, mut distance: F) -> Option<I>
+    where
+        T: Ord,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        Some(I::from_usize(match self.raw.binary_search(value) {
+            Ok(i) => i,
+            Err(0) => 0,
+            Err(i) if i == self.len() => i - 1,
+            Err(i) => {
+                if distance(&self.raw[i]) < distance(&self.raw[i - 1]) {
+                    i
+                } else {
+                    i - 1
+                }
+            }
+        }))
+    }
+
+    /// Returns the index where `value` could be inserted to keep this sorted
+    /// slice sorted. If `value` is already present, this is the index of a
+    /// matching element, as with [`IndexSlice::binary_search`].
+    #[inline]
+    pub fn search_insert_point(&self, value: &T) -> I
+    where
+        T: Ord,
+    {
+        match self.binary_search(value) {
+            Ok(i) | Err(i) => i,
+        }
+    }
+
     /// Searches for an element in an iterator, returning its index. This is
     /// equivalent to `Iterator::position`, but returns `I` and not `usize`.
     #[inline(always)]
@@ -351,6 +751,40 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         self.raw.iter().position(f).map(I::from_usize)
     }
 
+    /// Collects the indices of all the items matching `pred`, into an
+    /// `IndexVec` with its own index type `J`.
+    ///
+    /// Space for `self.len()` indices is reserved up front, so this never
+    /// reallocates (at the cost of possibly over-allocating).
+    pub fn collect_indices_where<J: Idx, F: FnMut(&T) -> bool>(
+        &self,
+        mut pred: F,
+    ) -> IndexVec<J, I> {
+        let mut out = IndexVec::with_capacity(self.len());
+        for (i, t) in self.iter_enumerated() {
+            if pred(t) {
+                out.push(i);
+            }
+        }
+        out
+    }
+
+    /// Like `self.iter().max_by_key(f)`, but also returns the item's index. As
+    /// with `Iterator::max_by_key`, if several items are maximal, the last is
+    /// returned.
+    #[inline]
+    pub fn max_by_key_enumerated<K: Ord, F: FnMut(&T) -> K>(&self, mut f: F) -> Option<(I, &T)> {
+        self.iter_enumerated().max_by_key(|&(_, t)| f(t))
+    }
+
+    /// Like `self.iter().min_by_key(f)`, but also returns the item's index. As
+    /// with `Iterator::min_by_key`, if several items are minimal, the first is
+    /// returned.
+    #[inline]
+    pub fn min_by_key_enumerated<K: Ord, F: FnMut(&T) -> K>(&self, mut f: F) -> Option<(I, &T)> {
+        self.iter_enumerated().min_by_key(|&(_, t)| f(t))
+    }
+
     /// Searches for an element in an iterator from the right, returning its
     /// index. This is equivalent to `Iterator::position`, but returns `I` and
     /// not `usize`.
@@ -359,6 +793,14 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         self.raw.iter().rposition(f).map(I::from_usize)
     }
 
+    /// Like `slice::fill_with`, but `f` is passed the index of each item.
+    #[inline]
+    pub fn fill_with_idx<F: FnMut(I) -> T>(&mut self, mut f: F) {
+        for (i, t) in self.iter_mut_enumerated() {
+            *t = f(i);
+        }
+    }
+
     /// Swaps two elements in our vector.
     #[inline]
     pub fn swap(&mut self, a: I, b: I) {
@@ -366,6 +808,9 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
     }
 
     /// Divides our slice into two at an index.
+    ///
+    /// Note that both halves are indexed from zero: the item at `a` in `self`
+    /// is at index 0 in the second half, not at `a`.
     #[inline]
     pub fn split_at(&self, a: I) -> (&Self, &Self) {
         let (a, b) = self.raw.split_at(a.index());
@@ -373,6 +818,9 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
     }
 
     /// Divides our slice into two at an index.
+    ///
+    /// Note that both halves are indexed from zero: the item at `a` in `self`
+    /// is at index 0 in the second half, not at `a`.
     #[inline]
     pub fn split_at_mut(&mut self, a: I) -> (&mut Self, &mut Self) {
         let (a, b) = self.raw.split_at_mut(a.index());
@@ -387,6 +835,16 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         self.raw.rotate_left(mid.index())
     }
 
+    /// Like [`IndexSlice::rotate_left`], but also returns a function mapping
+    /// each item's old index to its new one, for fixing up any indices into
+    /// this slice that are stored elsewhere.
+    #[inline]
+    pub fn rotate_left_remap(&mut self, mid: I) -> impl Fn(I) -> I {
+        self.rotate_left(mid);
+        let (len, mid) = (self.len(), mid.index());
+        move |old: I| I::from_usize((old.index() + len - mid) % len.max(1))
+    }
+
     /// Rotates our data in-place such that the first `self.len() - k` elements
     /// of the slice move to the end while the last `k` elements move to the
     /// front
@@ -443,6 +901,54 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         index.get_mut(self)
     }
 
+    /// Get a ref to the item at `index`, or None if `index` is None or out of
+    /// bounds.
+    #[inline]
+    pub fn get_opt(&self, index: Option<I>) -> Option<&T> {
+        self.raw.get(index?.index())
+    }
+
+    /// Get a mut ref to the item at `index`, or None if `index` is None or out
+    /// of bounds.
+    #[inline]
+    pub fn get_opt_mut(&mut self, index: Option<I>) -> Option<&mut T> {
+        self.raw.get_mut(index?.index())
+    }
+
+    /// Like `&self[range]`, but clamps `range` to our bounds, returning an
+    /// empty slice rather than panicking if it lies (partly or wholly) outside
+    /// of them.
+    #[inline]
+    pub fn get_or_empty<R: IdxRangeBounds<I>>(&self, range: R) -> &Self {
+        use core::ops::{Bound, RangeBounds};
+        let range = range.into_range();
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.saturating_add(1),
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => len,
+        };
+        let end = end.min(len);
+        Self::new(&self.raw[start.min(end)..end])
+    }
+
+    /// Get a ref to the item at the index given by the const parameter `N`.
+    ///
+    /// This is intended for generic fixed-layout code, where the index is known
+    /// at compile time. It doesn't go through `I::from_usize`, so it's just as
+    /// cheap as `self.raw[N]`.
+    ///
+    /// Panics if `N` is out of bounds.
+    #[inline(always)]
+    pub fn const_get<const N: usize>(&self) -> &T {
+        &self.raw[N]
+    }
+
     /// Wraps the underlying slice's `windows` iterator with one that yields
     /// `IndexSlice`s with the correct index type.
     #[inline]
@@ -457,6 +963,27 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
         self.raw.chunks(size).map(IndexSlice::new)
     }
 
+    /// Divides our indices into consecutive ranges of `size` items each (the
+    /// last range may be shorter), indexed by `C`.
+    ///
+    /// This is like [`IndexSlice::chunks`], but gives the index range of each
+    /// chunk rather than the chunk itself, which is useful for handing work out
+    /// (e.g. to other threads) that will index back into `self` later.
+    ///
+    /// Panics if `size` is 0.
+    #[inline]
+    pub fn chunk_descriptors<C: Idx>(&self, size: usize) -> IndexVec<C, Range<I>> {
+        assert!(size != 0, "chunk size must be non-zero");
+        let len = self.len();
+        (0..len)
+            .step_by(size)
+            .map(|start| {
+                let end = start + core::cmp::min(size, len - start);
+                I::from_usize(start)..I::from_usize(end)
+            })
+            .collect()
+    }
+
     /// Wraps the underlying slice's `chunks_mut` iterator with one that yields
     /// `IndexSlice`s with the correct index type.
     #[inline]
@@ -662,6 +1189,42 @@ impl<I: Idx, T> IndexSlice<I, [T]> {
     }
 }
 
+impl<I: Idx, J: Idx> IndexSlice<I, [J]> {
+    /// Treating `self` as a permutation (mapping each position to a distinct
+    /// target), returns the inverse mapping, so that `inv[self[i]] == i`. This
+    /// comes up after sorting a list of indices.
+    ///
+    /// Panics if `self` isn't a permutation of `0..self.len()`.
+    pub fn invert(&self) -> IndexVec<J, I> {
+        let mut inv: IndexVec<J, Option<I>> = IndexVec::from_vec(vec![None; self.len()]);
+        for (i, &j) in self.iter_enumerated() {
+            let prev = inv[j].replace(i);
+            debug_assert!(prev.is_none(), "invert: {:?} appears twice", j);
+        }
+        inv.into_iter()
+            .map(|i| i.expect("invert: not a permutation"))
+            .collect()
+    }
+}
+
+#[cfg(feature = "rayon")]
+type ParEnumerated<Iter, I, T> =
+    rayon::iter::Map<rayon::iter::Enumerate<Iter>, fn((usize, T)) -> (I, T)>;
+
+#[cfg(feature = "rayon")]
+impl<I: Idx + Send, T: Sync> IndexSlice<I, [T]> {
+    /// Similar to `self.par_iter().enumerate()` but with indices of `I` and not
+    /// `usize`. Requires the `rayon` feature.
+    #[inline]
+    pub fn par_iter_enumerated(&self) -> ParEnumerated<rayon::slice::Iter<'_, T>, I, &T> {
+        use rayon::prelude::*;
+        self.raw
+            .par_iter()
+            .enumerate()
+            .map(|(i, t)| (I::from_usize(i), t))
+    }
+}
+
 impl<I: Idx, A, B> PartialEq<IndexSlice<I, [B]>> for IndexSlice<I, [A]>
 where
     A: PartialEq<B>,
@@ -775,7 +1338,7 @@ impl<'a, I: Idx, T> From<&'a mut [T]> for &'a mut IndexSlice<I, [T]> {
 impl<I: Idx, T> From<Box<[T]>> for Box<IndexSlice<I, [T]>> {
     #[inline]
     fn from(b: Box<[T]>) -> Self {
-        unsafe { Box::from_raw(Box::into_raw(b) as *mut IndexSlice<I, [T]>) }
+        IndexSlice::from_boxed_slice(b)
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::{Idx, IndexSlice};
+use crate::{Idx, IndexSlice, IndexVec};
 
 mod private_slice_index {
     pub trait Sealed {}
@@ -17,6 +17,21 @@ pub trait IdxSliceIndex<I: Idx, T>: private_slice_index::Sealed {
     fn index_mut(self, slice: &mut IndexSlice<I, [T]>) -> &mut Self::Output;
 }
 
+/// Shorthand for an [`IdxSliceIndex`] that produces a subslice, e.g. any of
+/// the `Range*` types. Note that the second parameter is the element type, and
+/// not the slice type like with `core::slice::SliceIndex`, so the equivalent
+/// of `R: SliceIndex<[u8], Output = [u8]>` is `R: IdxSliceRange<I, u8>`, which
+/// in turn means `R: IdxSliceIndex<I, u8, Output = IndexSlice<I, [u8]>>`.
+///
+/// This is blanket-implemented for everything matching that bound, so it's
+/// only useful in bounds of generic functions.
+pub trait IdxSliceRange<I: Idx, T>: IdxSliceIndex<I, T, Output = IndexSlice<I, [T]>> {}
+
+impl<I: Idx, T, R> IdxSliceRange<I, T> for R where
+    R: IdxSliceIndex<I, T, Output = IndexSlice<I, [T]>>
+{
+}
+
 // Does this defeat the point of sealing?
 impl<I: Idx> private_slice_index::Sealed for I {}
 
@@ -83,6 +98,42 @@ range_slice!(core::ops::RangeFrom<I>);
 range_slice!(core::ops::RangeTo<I>);
 range_slice!(core::ops::RangeInclusive<I>);
 range_slice!(core::ops::RangeToInclusive<I>);
+
+// As with `usize` below, accept plain `usize` ranges too. These don't overlap
+// with the impls above, since `usize` isn't an `Idx`.
+macro_rules! usize_range_slice {
+    ($r:ty) => {
+        impl private_slice_index::Sealed for $r {}
+        impl<I: Idx, T> IdxSliceIndex<I, T> for $r {
+            type Output = IndexSlice<I, [T]>;
+
+            #[inline]
+            fn get(self, slice: &IndexSlice<I, [T]>) -> Option<&Self::Output> {
+                slice.raw.get(self).map(IndexSlice::new)
+            }
+            #[inline]
+            fn get_mut(self, slice: &mut IndexSlice<I, [T]>) -> Option<&mut Self::Output> {
+                slice.raw.get_mut(self).map(IndexSlice::new_mut)
+            }
+
+            #[inline]
+            fn index(self, slice: &IndexSlice<I, [T]>) -> &Self::Output {
+                IndexSlice::new(&slice.raw[self])
+            }
+            #[inline]
+            fn index_mut(self, slice: &mut IndexSlice<I, [T]>) -> &mut Self::Output {
+                IndexSlice::new_mut(&mut slice.raw[self])
+            }
+        }
+    };
+}
+
+usize_range_slice!(core::ops::Range<usize>);
+usize_range_slice!(core::ops::RangeFrom<usize>);
+usize_range_slice!(core::ops::RangeTo<usize>);
+usize_range_slice!(core::ops::RangeInclusive<usize>);
+usize_range_slice!(core::ops::RangeToInclusive<usize>);
+
 // range_slice!(core::ops::RangeFull);
 impl private_slice_index::Sealed for core::ops::RangeFull {}
 impl<I: Idx, T> IdxSliceIndex<I, T> for core::ops::RangeFull {
@@ -191,6 +242,9 @@ impl<I: Idx> IdxRangeBounds<I> for core::ops::RangeTo<I> {
     }
 }
 
+// Note: the inclusive ranges are converted to inclusive `usize` ranges (and not
+// to `start..end + 1`), so that an `end` of `usize::MAX` doesn't overflow, and
+// so that the bounds checks are left entirely to the slice impls.
 impl<I: Idx> IdxRangeBounds<I> for core::ops::RangeInclusive<I> {
     type Range = core::ops::RangeInclusive<usize>;
     #[inline]
@@ -229,3 +283,26 @@ where
         index.index_mut(self)
     }
 }
+
+impl<I, R, T> core::ops::Index<R> for IndexVec<I, T>
+where
+    I: Idx,
+    R: IdxSliceIndex<I, T>,
+{
+    type Output = R::Output;
+    #[inline]
+    fn index(&self, index: R) -> &R::Output {
+        index.index(self.as_slice())
+    }
+}
+
+impl<I, R, T> core::ops::IndexMut<R> for IndexVec<I, T>
+where
+    I: Idx,
+    R: IdxSliceIndex<I, T>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: R) -> &mut R::Output {
+        index.index_mut(self.as_mut_slice())
+    }
+}
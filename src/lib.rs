@@ -105,11 +105,24 @@
 //!
 //! #### Does it support no_std?
 //!
-//! Yes, although it uses `extern crate alloc;`, of course.
+//! Yes, although it uses `extern crate alloc;`, of course. A few things that
+//! need `std` (such as `IndexVec::from_sparse`) are behind the `std` feature.
 //!
 //! #### Does it support serde?
 //!
-//! Yes, but only if you turn on the `serde` feature.
+//! Yes, but only if you turn on the `serde` feature. This covers `IndexVec`,
+//! `IndexBox`, and the types from `define_index_type!` (which are serialized
+//! as bare integers, or as a one-field struct if declared with a named field,
+//! and fail to deserialize if they're past `MAX_INDEX`).
+//!
+//! #### Does it support rayon?
+//!
+//! A little: the `rayon` feature adds `par_iter_enumerated`.
+//!
+//! #### Can I get at the raw bytes?
+//!
+//! For `T: bytemuck::Pod`, the `bytemuck` feature adds `IndexSlice::as_bytes`,
+//! and (together with `std`) `IndexVec::write_raw` and `IndexVec::read_raw`.
 //!
 //! #### What features are planned?
 //!
@@ -130,9 +143,12 @@
 #![allow(clippy::partialeq_ne_impl)]
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::borrow::{Borrow, BorrowMut};
@@ -146,7 +162,7 @@ use core::slice;
 mod idxslice;
 mod indexing;
 pub use idxslice::{IndexBox, IndexSlice};
-pub use indexing::{IdxRangeBounds, IdxSliceIndex};
+pub use indexing::{IdxRangeBounds, IdxSliceIndex, IdxSliceRange};
 
 #[macro_use]
 mod macros;
@@ -181,10 +197,72 @@ pub trait Idx: Copy + 'static + Ord + Debug + Hash {
     /// are free to define what "fit" means as they desire.
     fn from_usize(idx: usize) -> Self;
 
+    /// Construct an Index from a usize, returning `None` instead of panicking
+    /// if `idx` does not fit.
+    ///
+    /// The default implementation just calls `from_usize`, so impls which can
+    /// fail should override this. Types from [`define_index_type!`] do, and
+    /// return `None` for anything past `MAX_INDEX` (unless checking has been
+    /// disabled, in which case this always returns `Some`).
+    #[inline]
+    fn try_from_usize(idx: usize) -> Option<Self> {
+        Some(Self::from_usize(idx))
+    }
+
     /// Get the underlying index. This is equivalent to `Into<usize>`
     fn index(self) -> usize;
 }
 
+/// Extension trait providing typed enumeration for arbitrary iterators.
+///
+/// ```rust
+/// use index_vec::IdxEnumerate;
+/// index_vec::define_index_type! { struct CharIdx = u32; }
+///
+/// # fn main() {
+/// let (i, c) = "abc".chars().enumerate_idx::<CharIdx>().last().unwrap();
+/// assert_eq!((i, c), (CharIdx::new(2), 'c'));
+/// # }
+/// ```
+pub trait IdxEnumerate: Iterator + Sized {
+    /// Similar to `self.enumerate()` but with indices of `I` and not `usize`.
+    fn enumerate_idx<I: Idx>(self) -> Enumerated<Self, I, Self::Item>;
+}
+
+impl<It: Iterator> IdxEnumerate for It {
+    #[inline]
+    fn enumerate_idx<I: Idx>(self) -> Enumerated<Self, I, Self::Item> {
+        self.enumerate().map(|(i, t)| (I::from_usize(i), t))
+    }
+}
+
+/// Extension trait for converting a `Vec<T>` into an `IndexVec<I, T>` while
+/// naming only the index type.
+///
+/// This is handy when the index type can't otherwise be inferred, e.g. at the
+/// end of a `collect()` chain:
+///
+/// ```rust
+/// use index_vec::IntoIndexVec;
+/// index_vec::define_index_type! { struct NodeIdx = u32; }
+///
+/// # fn main() {
+/// let nodes = (0..3).map(|i| i * 10).collect::<Vec<_>>().into_index_vec::<NodeIdx>();
+/// assert_eq!(nodes[NodeIdx::new(2)], 20);
+/// # }
+/// ```
+pub trait IntoIndexVec<T> {
+    /// Equivalent to [`IndexVec::from_vec`].
+    fn into_index_vec<I: Idx>(self) -> IndexVec<I, T>;
+}
+
+impl<T> IntoIndexVec<T> for Vec<T> {
+    #[inline]
+    fn into_index_vec<I: Idx>(self) -> IndexVec<I, T> {
+        IndexVec::from_vec(self)
+    }
+}
+
 /// A macro equivalent to the stdlib's `vec![]`, but producing an `IndexVec`.
 #[macro_export]
 macro_rules! index_vec {
@@ -261,6 +339,109 @@ impl<I: Idx, T> IndexVec<I, T> {
         }
     }
 
+    /// Construct an `IndexVec` from `(index, item)` pairs, which must arrive
+    /// densely and in order (e.g. `0, 1, 2, ...`), as produced by
+    /// `iter_enumerated`.
+    ///
+    /// Panics if there's a gap or an out-of-order index. See
+    /// [`IndexVec::try_from_enumerated`] for a non-panicking version.
+    #[inline]
+    pub fn from_enumerated<It: IntoIterator<Item = (I, T)>>(iter: It) -> Self {
+        let iter = iter.into_iter();
+        let mut v = Self::with_capacity(iter.size_hint().0);
+        for (i, t) in iter {
+            assert!(
+                i.index() == v.len(),
+                "IndexVec::from_enumerated: expected index {}, got {}",
+                v.len(),
+                i.index()
+            );
+            v.raw.push(t);
+        }
+        v
+    }
+
+    /// Like [`IndexVec::from_enumerated`], but on a gap or out-of-order index,
+    /// returns that index as an error instead of panicking.
+    pub fn try_from_enumerated<It: IntoIterator<Item = (I, T)>>(iter: It) -> Result<Self, I> {
+        let iter = iter.into_iter();
+        let mut v = Self::with_capacity(iter.size_hint().0);
+        for (i, t) in iter {
+            if i.index() != v.len() {
+                return Err(i);
+            }
+            v.raw.push(t);
+        }
+        Ok(v)
+    }
+
+    /// Construct a `IndexVec` from a `Vec<T>`. This is the same as
+    /// [`IndexVec::from_vec`], but reads better when used to pin down the index
+    /// type, e.g. `IndexVec::<MyIdx, _>::typed(v)`.
+    #[inline]
+    pub fn typed(vec: Vec<T>) -> Self {
+        Self::from_vec(vec)
+    }
+
+    /// Construct a dense `IndexVec` from a sparse map of index to value.
+    ///
+    /// The result is long enough to hold the largest key in `map`, and any
+    /// index not present in `map` is filled with `fill(index)`. Requires the
+    /// `std` feature.
+    #[cfg(feature = "std")]
+    pub fn from_sparse<S, F: FnMut(I) -> T>(
+        map: std::collections::HashMap<I, T, S>,
+        mut fill: F,
+    ) -> Self {
+        let len = map.keys().max().map_or(0, |i| i.index() + 1);
+        let mut slots: Vec<Option<T>> = iter::repeat_with(|| None).take(len).collect();
+        for (i, t) in map {
+            slots[i.index()] = Some(t);
+        }
+        Self::from_vec(
+            slots
+                .into_iter()
+                .enumerate()
+                .map(|(i, t)| t.unwrap_or_else(|| fill(I::from_usize(i))))
+                .collect(),
+        )
+    }
+
+    /// Construct an `IndexVec` holding `n` copies of `T::default()`.
+    ///
+    /// Panics if `n` is too large for our index type.
+    #[inline]
+    pub fn filled_default(n: usize) -> Self
+    where
+        T: Default,
+    {
+        // Check before allocating, rather than in `from_vec`.
+        let _ = I::from_usize(n);
+        Self::from_vec(iter::repeat_with(T::default).take(n).collect())
+    }
+
+    /// Construct an `IndexVec` of length `n` by calling `f` with each index in
+    /// order.
+    ///
+    /// Panics (before calling `f`) if `n` is too large for our index type.
+    #[inline]
+    pub fn from_fn_n<F: FnMut(I) -> T>(f: F, n: usize) -> Self {
+        // See if `I::from_usize` might be upset by this length.
+        let _ = I::from_usize(n);
+        (0..n).map(I::from_usize).map(f).collect()
+    }
+
+    /// Like [`IndexVec::from_fn_n`], but for a fallible `f`. Stops and returns
+    /// the first error `f` produces.
+    ///
+    /// Panics (before calling `f`) if `n` is too large for our index type.
+    #[inline]
+    pub fn try_from_fn_n<E, F: FnMut(I) -> Result<T, E>>(f: F, n: usize) -> Result<Self, E> {
+        // See if `I::from_usize` might be upset by this length.
+        let _ = I::from_usize(n);
+        (0..n).map(I::from_usize).map(f).collect()
+    }
+
     /// Construct an IndexVec that can hold at least `capacity` items before
     /// reallocating. See [`Vec::with_capacity`].
     #[inline]
@@ -271,6 +452,20 @@ impl<I: Idx, T> IndexVec<I, T> {
         }
     }
 
+    /// Like [`IndexVec::with_capacity`], but takes a typed [`Len`].
+    #[inline]
+    pub fn with_capacity_for(capacity: Len<I>) -> Self {
+        Self::with_capacity(capacity.get())
+    }
+
+    /// Converts us into a vector with a different index type, without copying.
+    ///
+    /// Panics if our length is too large for `J`.
+    #[inline]
+    pub fn into_reindexed<J: Idx>(self) -> IndexVec<J, T> {
+        IndexVec::from_vec(self.raw)
+    }
+
     /// Similar to `self.into_iter().enumerate()` but with indices of `I` and
     /// not `usize`.
     #[inline(always)]
@@ -281,6 +476,21 @@ impl<I: Idx, T> IndexVec<I, T> {
             .map(|(i, t)| (I::from_usize(i), t))
     }
 
+    /// Consumes the vector, building a new one with the same indices by calling
+    /// `f` with each index and item. See [`IndexSlice::map_collect`] for a
+    /// version which borrows instead.
+    #[inline]
+    pub fn map_enumerated<U, F: FnMut(I, T) -> U>(self, mut f: F) -> IndexVec<I, U> {
+        self.into_iter_enumerated().map(|(i, t)| f(i, t)).collect()
+    }
+
+    /// Consumes the vector, collecting its `(I, T)` pairs in reverse order.
+    /// Equivalent to `self.into_iter_enumerated().rev().collect()`.
+    #[inline]
+    pub fn rev_enumerated_collect<C: FromIterator<(I, T)>>(self) -> C {
+        self.into_iter_enumerated().rev().collect()
+    }
+
     /// Creates a splicing iterator that replaces the specified range in the
     /// vector with the given `replace_with` iterator and yields the removed
     /// items. See [`Vec::splice`]
@@ -289,7 +499,7 @@ impl<I: Idx, T> IndexVec<I, T> {
         &mut self,
         range: R,
         replace_with: It,
-    ) -> vec::Splice<<It as IntoIterator>::IntoIter>
+    ) -> vec::Splice<'_, <It as IntoIterator>::IntoIter>
     where
         It: IntoIterator<Item = T>,
         R: IdxRangeBounds<I>,
@@ -310,6 +520,13 @@ impl<I: Idx, T> IndexVec<I, T> {
             .map(|(i, t)| (I::from_usize(i), t))
     }
 
+    /// Converts this into a `BTreeMap` from each index to its item. Since `I:
+    /// Ord`, iterating the map visits the items in index order.
+    #[inline]
+    pub fn into_btreemap(self) -> BTreeMap<I, T> {
+        self.into_iter_enumerated().collect()
+    }
+
     /// Gives the next index that will be assigned when `push` is
     /// called.
     #[inline]
@@ -317,6 +534,19 @@ impl<I: Idx, T> IndexVec<I, T> {
         I::from_usize(self.len())
     }
 
+    /// Asserts that `self.next_idx() == expected_next`, e.g. to catch this
+    /// vector falling out of sync with some other structure that's meant to
+    /// cover the same index domain.
+    #[inline]
+    pub fn assert_len_idx(&self, expected_next: I) {
+        assert!(
+            self.len() == expected_next.index(),
+            "IndexVec length mismatch: expected next index {:?}, but have {} items",
+            expected_next,
+            self.len(),
+        );
+    }
+
     /// Get a the storage as a `&[T]`
     #[inline(always)]
     pub fn as_raw_slice(&self) -> &[T] {
@@ -350,6 +580,16 @@ impl<I: Idx, T> IndexVec<I, T> {
         idx
     }
 
+    /// Like [`IndexVec::push`], but if there's no spare capacity, gives `d`
+    /// back as an error rather than reallocating.
+    #[inline]
+    pub fn push_within_capacity(&mut self, d: T) -> Result<I, T> {
+        if self.raw.len() == self.raw.capacity() {
+            return Err(d);
+        }
+        Ok(self.push(d))
+    }
+
     /// Pops the last item off, returning it. See [`Vec::pop`].
     #[inline]
     pub fn pop(&mut self) -> Option<T> {
@@ -386,18 +626,42 @@ impl<I: Idx, T> IndexVec<I, T> {
         self.raw.truncate(a)
     }
 
+    /// Like [`IndexVec::truncate`], but takes a typed [`Len`].
+    #[inline]
+    pub fn truncate_to(&mut self, len: Len<I>) {
+        self.raw.truncate(len.get())
+    }
+
     /// Clear our vector. See [`Vec::clear`].
     #[inline]
     pub fn clear(&mut self) {
         self.raw.clear()
     }
 
+    /// Clear our vector, and then shrink its capacity to at most
+    /// `keep_capacity`, so that a long-lived, reused vector doesn't hold onto
+    /// its peak memory usage forever. See [`Vec::shrink_to`].
+    #[inline]
+    pub fn clear_and_shrink(&mut self, keep_capacity: usize) {
+        self.raw.clear();
+        self.raw.shrink_to(keep_capacity);
+    }
+
     /// Reserve capacity for `c` more elements. See [`Vec::reserve`]
     #[inline]
     pub fn reserve(&mut self, c: usize) {
         self.raw.reserve(c)
     }
 
+    /// Like [`IndexVec::reserve`], but returns true if our capacity grew (which
+    /// means we reallocated).
+    #[inline]
+    pub fn reserve_tracked(&mut self, c: usize) -> bool {
+        let old_capacity = self.raw.capacity();
+        self.raw.reserve(c);
+        self.raw.capacity() != old_capacity
+    }
+
     /// Get a ref to the item at the provided index, or None for out of bounds.
     #[inline]
     pub fn get<J: IdxSliceIndex<I, T>>(&self, index: J) -> Option<&J::Output> {
@@ -412,20 +676,70 @@ impl<I: Idx, T> IndexVec<I, T> {
     }
 
     /// Resize ourselves in-place to `new_len`. See [`Vec::resize`].
+    ///
+    /// Panics (before allocating) if `new_len` is too large for our index type.
     #[inline]
     pub fn resize(&mut self, new_len: usize, value: T)
     where
         T: Clone,
     {
+        // See if `I::from_usize` might be upset by this length.
+        let _ = I::from_usize(new_len);
         self.raw.resize(new_len, value)
     }
 
+    /// Like [`IndexVec::resize`], but takes a typed [`Len`].
+    #[inline]
+    pub fn resize_to(&mut self, new_len: Len<I>, value: T)
+    where
+        T: Clone,
+    {
+        self.raw.resize(new_len.get(), value)
+    }
+
     /// Resize ourselves in-place to `new_len`. See [`Vec::resize_with`].
+    ///
+    /// Panics (before allocating) if `new_len` is too large for our index type.
     #[inline]
     pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, f: F) {
+        // See if `I::from_usize` might be upset by this length.
+        let _ = I::from_usize(new_len);
         self.raw.resize_with(new_len, f)
     }
 
+    /// Grows ourselves (if needed) so that `idx` is in bounds, filling any new
+    /// slots with `T::default()`.
+    #[inline]
+    pub fn ensure_index(&mut self, idx: I)
+    where
+        T: Default,
+    {
+        self.get_or_insert_with(idx, T::default);
+    }
+
+    /// Grows ourselves (if needed) so that `idx` is in bounds, filling any new
+    /// slots with clones of `value`.
+    #[inline]
+    pub fn grow_to(&mut self, idx: I, value: T)
+    where
+        T: Clone,
+    {
+        if idx.index() >= self.len() {
+            self.raw.resize(idx.index() + 1, value);
+        }
+    }
+
+    /// Returns a mutable reference to `self[idx]`, first growing ourselves (if
+    /// needed) so that `idx` is in bounds, and filling any new slots by calling
+    /// `f`.
+    #[inline]
+    pub fn get_or_insert_with<F: FnMut() -> T>(&mut self, idx: I, f: F) -> &mut T {
+        if idx.index() >= self.len() {
+            self.raw.resize_with(idx.index() + 1, f);
+        }
+        &mut self.raw[idx.index()]
+    }
+
     /// Moves all the elements of `other` into `Self`, leaving `other` empty.
     /// See [`Vec::append`].
     #[inline]
@@ -440,6 +754,19 @@ impl<I: Idx, T> IndexVec<I, T> {
         Self::from_vec(self.raw.split_off(idx.index()))
     }
 
+    /// Like [`IndexVec::split_off`], but returns the removed items paired with
+    /// the indices they had in `self` (rather than rebased to start at zero).
+    #[inline]
+    pub fn split_off_enumerated(&mut self, idx: I) -> Vec<(I, T)> {
+        let start = idx.index();
+        self.raw
+            .split_off(start)
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| (I::from_usize(start + i), t))
+            .collect()
+    }
+
     /// Remove the item at `index`. See [`Vec::remove`].
     #[inline]
     pub fn remove(&mut self, index: I) -> T {
@@ -476,6 +803,58 @@ impl<I: Idx, T> IndexVec<I, T> {
         self.raw.retain(f)
     }
 
+    /// Removes every item whose entry in `to_remove` is true, in a single pass,
+    /// and returns a map from each old index to its new index (or `None` for
+    /// the removed items).
+    ///
+    /// Panics if `to_remove` and `self` have different lengths.
+    pub fn remove_indices(&mut self, to_remove: &IndexSlice<I, [bool]>) -> IndexVec<I, Option<I>> {
+        assert_eq!(
+            to_remove.len(),
+            self.len(),
+            "`to_remove` must have an entry for every item"
+        );
+        let mut next = 0;
+        let map = to_remove
+            .iter()
+            .map(|&remove| {
+                if remove {
+                    None
+                } else {
+                    next += 1;
+                    Some(I::from_usize(next - 1))
+                }
+            })
+            .collect();
+        let mut flags = to_remove.iter();
+        self.raw.retain(|_| !flags.next().unwrap());
+        map
+    }
+
+    /// The inverse of [`IndexSlice::gather`]: writes `self[indices[j]] =
+    /// values[j]` for each `j`. If `indices` contains duplicates, the last
+    /// write to a given position wins.
+    ///
+    /// Panics if `indices` and `values` have different lengths, or if any of
+    /// `indices` are out of bounds.
+    pub fn scatter<J: Idx>(&mut self, indices: &IndexSlice<J, [I]>, values: IndexVec<J, T>) {
+        assert_eq!(
+            indices.len(),
+            values.len(),
+            "`indices` must have an entry for every value"
+        );
+        let len = self.len();
+        for (&i, v) in indices.iter().zip(values) {
+            assert!(
+                i.index() < len,
+                "scatter target index {} out of bounds (len is {})",
+                i.index(),
+                len
+            );
+            self.raw[i.index()] = v;
+        }
+    }
+
     /// Forwards to the `Vec::dedup_by_key` implementation.
     #[inline]
     pub fn dedup_by_key<F: FnMut(&mut T) -> K, K: PartialEq>(&mut self, key: F) {
@@ -491,12 +870,106 @@ impl<I: Idx, T> IndexVec<I, T> {
         self.raw.dedup()
     }
 
+    /// Sorts the vector (with a stable sort) and then removes duplicates,
+    /// leaving each distinct value exactly once.
+    #[inline]
+    pub fn sort_dedup(&mut self)
+    where
+        T: Ord,
+    {
+        self.raw.sort();
+        self.raw.dedup();
+    }
+
+    /// Like [`IndexVec::sort_dedup`], but uses an unstable sort.
+    #[inline]
+    pub fn sort_unstable_dedup(&mut self)
+    where
+        T: Ord,
+    {
+        self.raw.sort_unstable();
+        self.raw.dedup();
+    }
+
+    /// Returns true if `self` and `other` hold the same items (including
+    /// duplicates), ignoring their order. This sorts copies of both, so it's
+    /// mostly intended for tests and sanity checks.
+    pub fn eq_unordered(&self, other: &Self) -> bool
+    where
+        T: Ord + Clone,
+    {
+        if self.len() != other.len() {
+            return false;
+        }
+        let mut a = self.raw.clone();
+        let mut b = other.raw.clone();
+        a.sort_unstable();
+        b.sort_unstable();
+        a == b
+    }
+
     /// Forwards to the `Vec::dedup_by` implementation.
     #[inline]
     pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same_bucket: F) {
         self.raw.dedup_by(same_bucket)
     }
 
+    /// Like [`IndexVec::dedup_by_key`], but also returns a map from each
+    /// item's original index to the index of the item it was collapsed into.
+    ///
+    /// This is the core of a simple interner: push candidate values, then
+    /// collapse consecutive duplicates and use the returned map to rewrite any
+    /// indices that were handed out before.
+    pub fn intern_consecutive<F: FnMut(&mut T) -> K, K: PartialEq>(
+        &mut self,
+        mut key: F,
+    ) -> IndexVec<I, I> {
+        let len = self.len();
+        let mut map = IndexVec::with_capacity(len);
+        let mut write = 0;
+        for read in 0..len {
+            if write == 0 || key(&mut self.raw[read]) != key(&mut self.raw[write - 1]) {
+                self.raw.swap(read, write);
+                write += 1;
+            }
+            map.push(I::from_usize(write - 1));
+        }
+        self.raw.truncate(write);
+        map
+    }
+
+    /// Removes every item whose key equals that of an earlier item (anywhere
+    /// in the vector, not just consecutively like [`IndexVec::intern_consecutive`]),
+    /// and returns the canonicalization table: for each *old* index (so it has
+    /// the vector's original length), the *new* index of the item which now
+    /// stands in for it.
+    ///
+    /// The first item with each key is kept, and the kept items stay in their
+    /// original order. Keys are only compared with `PartialEq`, so this is
+    /// quadratic in the number of distinct keys.
+    pub fn canonicalize_by_key<F: FnMut(&mut T) -> K, K: PartialEq>(
+        &mut self,
+        mut key: F,
+    ) -> IndexVec<I, I> {
+        let len = self.len();
+        let mut map = IndexVec::with_capacity(len);
+        let mut keys = Vec::new();
+        for read in 0..len {
+            let k = key(&mut self.raw[read]);
+            let new = match keys.iter().position(|seen| *seen == k) {
+                Some(new) => new,
+                None => {
+                    self.raw.swap(read, keys.len());
+                    keys.push(k);
+                    keys.len() - 1
+                }
+            };
+            map.push(I::from_usize(new));
+        }
+        self.raw.truncate(keys.len());
+        map
+    }
+
     /// Get a IndexSlice over this vector. See `as_raw_slice` for converting to
     /// a `&[T]` (or access `self.raw`).
     #[inline(always)]
@@ -512,6 +985,26 @@ impl<I: Idx, T> IndexVec<I, T> {
     }
 }
 
+impl<I: Idx, A, B> IndexVec<I, (A, B)> {
+    /// Splits a vector of pairs into a pair of vectors, which share our index
+    /// type. See `Iterator::unzip`.
+    #[inline]
+    pub fn unzip(self) -> (IndexVec<I, A>, IndexVec<I, B>) {
+        let (a, b) = self.raw.into_iter().unzip();
+        (IndexVec::from_vec(a), IndexVec::from_vec(b))
+    }
+
+    /// Pairs up the items of two vectors which share an index type. This is the
+    /// inverse of [`IndexVec::unzip`].
+    ///
+    /// Panics if `a` and `b` aren't the same length.
+    #[inline]
+    pub fn zip(a: IndexVec<I, A>, b: IndexVec<I, B>) -> Self {
+        assert_eq!(a.len(), b.len(), "IndexVec::zip: length mismatch");
+        IndexVec::from_vec(a.raw.into_iter().zip(b.raw).collect())
+    }
+}
+
 impl<I: Idx, T> Default for IndexVec<I, T> {
     #[inline]
     fn default() -> Self {
@@ -623,6 +1116,13 @@ impl<I: Idx, T> From<Vec<T>> for IndexVec<I, T> {
     }
 }
 
+impl<I: Idx, T, const N: usize> From<[T; N]> for IndexVec<I, T> {
+    #[inline]
+    fn from(arr: [T; N]) -> Self {
+        Self::from_vec(Vec::from(arr))
+    }
+}
+
 impl<I: Idx, T: Clone> Clone for IndexVec<I, T> {
     #[inline]
     fn clone(&self) -> Self {
@@ -694,6 +1194,187 @@ impl<I: Idx, T> BorrowMut<IndexSlice<I, [T]>> for IndexVec<I, T> {
     }
 }
 
+#[cfg(feature = "std")]
+impl<I: Idx> std::io::Write for IndexVec<I, u8> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.raw.write(buf)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.raw.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.raw.flush()
+    }
+}
+
+#[cfg(all(feature = "bytemuck", feature = "std"))]
+impl<I: Idx, T: bytemuck::Pod> IndexVec<I, T> {
+    /// Writes our length (as a little-endian `u64`), followed by the bytes of
+    /// our items. Requires the `bytemuck` and `std` features.
+    ///
+    /// The items are written in native byte order, so the output can only be
+    /// read back by [`IndexVec::read_raw`] on a machine of the same endianness.
+    pub fn write_raw<W: std::io::Write>(&self, mut w: W) -> std::io::Result<()> {
+        w.write_all(&(self.len() as u64).to_le_bytes())?;
+        w.write_all(self.as_bytes())
+    }
+
+    /// Reads a vector written by [`IndexVec::write_raw`]. Requires the
+    /// `bytemuck` and `std` features.
+    ///
+    /// Fails with `InvalidData` if the length is too large for our index type
+    /// (or for memory), and with `UnexpectedEof` if the input ends early. The
+    /// length isn't trusted for allocation: we only grow the vector as the data
+    /// actually arrives.
+    pub fn read_raw<R: std::io::Read>(mut r: R) -> std::io::Result<Self> {
+        let mut len = [0u8; 8];
+        r.read_exact(&mut len)?;
+        let len = <usize as core::convert::TryFrom<u64>>::try_from(u64::from_le_bytes(len))
+            .ok()
+            .filter(|&n| I::try_from_usize(n).is_some())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "length is too large for the index type",
+                )
+            })?;
+        let size = core::mem::size_of::<T>();
+        if size == 0 {
+            return Ok(Self::from_vec(vec![T::zeroed(); len]));
+        }
+        if len.checked_mul(size).is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "length is too large to fit in memory",
+            ));
+        }
+        // Read in bounded chunks, so that a bogus length in the header gets us
+        // an `UnexpectedEof` rather than a huge up-front allocation.
+        let chunk = (64 * 1024 / size).max(1);
+        let mut raw = Vec::new();
+        while raw.len() < len {
+            let start = raw.len();
+            raw.resize(start + chunk.min(len - start), T::zeroed());
+            r.read_exact(bytemuck::cast_slice_mut(&mut raw[start..]))?;
+        }
+        Ok(Self::from_vec(raw))
+    }
+}
+
+/// A thin wrapper around an [`IndexVec`] which is built by pushing items at
+/// known indices, asserting that each push lands where the caller expected.
+///
+/// This is mostly useful in tests and for debugging, to catch cases where some
+/// parallel structure has fallen out of sync with the vector's indices.
+///
+/// ```
+/// use index_vec::{IndexVec, IndexVecBuilder};
+/// index_vec::define_index_type! { struct NodeIdx = u32; }
+///
+/// # fn main() {
+/// let mut b = IndexVecBuilder::<NodeIdx, &str>::new();
+/// b.push_expect(NodeIdx::new(0), "a");
+/// b.push_expect(NodeIdx::new(1), "b");
+/// let v: IndexVec<NodeIdx, &str> = b.finish();
+/// assert_eq!(v, ["a", "b"]);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexVecBuilder<I: Idx, T> {
+    vec: IndexVec<I, T>,
+}
+
+impl<I: Idx, T> IndexVecBuilder<I, T> {
+    /// Construct a new, empty builder.
+    #[inline]
+    pub fn new() -> Self {
+        IndexVecBuilder {
+            vec: IndexVec::new(),
+        }
+    }
+
+    /// Pushes `value`, panicking if it was not assigned the index `expected`.
+    #[inline]
+    pub fn push_expect(&mut self, expected: I, value: T) {
+        let got = self.vec.push(value);
+        assert!(
+            got == expected,
+            "IndexVecBuilder push mismatch: expected index {:?}, but got {:?}",
+            expected,
+            got,
+        );
+    }
+
+    /// Returns the vector that's been built so far.
+    #[inline]
+    pub fn finish(self) -> IndexVec<I, T> {
+        self.vec
+    }
+}
+
+impl<I: Idx, T> Default for IndexVecBuilder<I, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A length of (or count of items in) an `IndexVec<I, _>` or
+/// `IndexSlice<I, _>`.
+///
+/// This exists so that methods which take a length, such as
+/// [`IndexVec::truncate_to`], can't be accidentally passed a position (e.g.
+/// `last_idx()`, where `last_idx() + 1` was meant), which is an easy mistake
+/// to make when both are `usize`.
+///
+/// ```compile_fail
+/// # use index_vec::{IndexVec, index_vec};
+/// index_vec::define_index_type! { struct Ix = u32; }
+/// # fn main() {
+/// let mut v: IndexVec<Ix, u8> = index_vec![1, 2, 3];
+/// // Error: expected a `Len<Ix>`, found `Ix`.
+/// v.truncate_to(v.last_idx());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Len<I: Idx> {
+    len: usize,
+    _marker: PhantomData<fn(&I)>,
+}
+
+impl<I: Idx> Len<I> {
+    /// Construct a length of `n` items.
+    ///
+    /// Panics if `n` is too large for our index type.
+    #[inline]
+    pub fn new(n: usize) -> Self {
+        // See if `I::from_usize` might be upset by this length.
+        let _ = I::from_usize(n);
+        Len {
+            len: n,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The length of the items before `idx`, e.g. of the range `0..idx`. This
+    /// is also the length which has `idx` as its `next_idx()`.
+    #[inline]
+    pub fn of(idx: I) -> Self {
+        Self::new(idx.index())
+    }
+
+    /// Get the length as a `usize`.
+    #[inline]
+    pub fn get(self) -> usize {
+        self.len
+    }
+}
+
 macro_rules! impl_partialeq {
     ($Lhs: ty, $Rhs: ty) => {
         impl<'a, 'b, A, B, I: Idx> PartialEq<$Rhs> for $Lhs
@@ -767,6 +1448,72 @@ array_impls! {
     30 31 32
 }
 
+/// Formats an index-keyed table with one row per index in `0..len`, and a
+/// column for each of `columns` (given as a header and a function producing
+/// that column's cell for an index). This is handy for looking at several
+/// parallel `IndexVec`s side by side. Requires the `std` feature.
+///
+/// ```
+/// # use index_vec::{index_vec, IndexVec};
+/// index_vec::define_index_type! { struct Ix = u32; }
+/// # fn main() {
+/// let names: IndexVec<Ix, &str> = index_vec!["x", "long"];
+/// let sizes: IndexVec<Ix, u32> = index_vec![4, 16];
+/// let table = index_vec::debug_table::<Ix>(
+///     &[
+///         ("name", &|i| names[i].to_string()),
+///         ("size", &|i| sizes[i].to_string()),
+///     ],
+///     names.len(),
+/// );
+/// assert_eq!(table, "\
+/// idx | name | size
+/// 0   | x    | 4
+/// 1   | long | 16
+/// ");
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn debug_table<I: Idx>(
+    columns: &[(&str, &dyn Fn(I) -> std::string::String)],
+    len: usize,
+) -> std::string::String {
+    use std::string::{String, ToString};
+    let mut cells: Vec<Vec<String>> = Vec::with_capacity(len + 1);
+    cells.push(
+        iter::once("idx".to_string())
+            .chain(columns.iter().map(|(name, _)| name.to_string()))
+            .collect(),
+    );
+    for i in (0..len).map(I::from_usize) {
+        cells.push(
+            iter::once(std::format!("{:?}", i))
+                .chain(columns.iter().map(|(_, f)| f(i)))
+                .collect(),
+        );
+    }
+    let widths: Vec<usize> = (0..=columns.len())
+        .map(|c| {
+            cells
+                .iter()
+                .map(|row| row[c].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut out = String::new();
+    for row in &cells {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &w)| std::format!("{:w$}", cell, w = w))
+            .collect();
+        out.push_str(line.join(" | ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
 #[inline(never)]
 #[cold]
 #[doc(hidden)]
@@ -777,6 +1524,51 @@ pub fn __max_check_fail(u: usize, max: usize) -> ! {
     )
 }
 
+/// The error returned when parsing an index type (generated with the `PARSE`
+/// option of [`define_index_type!`]) from a string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseIdxError {
+    /// The string wasn't a valid integer of the index's raw type.
+    Int(core::num::ParseIntError),
+    /// The value parsed fine, but is above the index type's `MAX_INDEX`.
+    TooLarge(usize),
+}
+
+impl fmt::Display for ParseIdxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseIdxError::Int(e) => fmt::Display::fmt(e, f),
+            ParseIdxError::TooLarge(u) => write!(f, "index {} is too large", u),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseIdxError {}
+
+/// The error returned when converting an integer to an index type (via the
+/// `TryFrom` impls generated with the `IMPL_TRY_FROM` option of
+/// [`define_index_type!`]) fails, because it was negative or above the index
+/// type's `MAX_INDEX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdxOverflowError(());
+
+impl IdxOverflowError {
+    #[doc(hidden)]
+    pub const fn __new() -> Self {
+        IdxOverflowError(())
+    }
+}
+
+impl fmt::Display for IdxOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("integer out of range for index type")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IdxOverflowError {}
+
 #[cfg(feature = "serde")]
 impl<I: Idx, T: serde::ser::Serialize> serde::ser::Serialize for IndexVec<I, T> {
     fn serialize<S: serde::ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -787,7 +1579,9 @@ impl<I: Idx, T: serde::ser::Serialize> serde::ser::Serialize for IndexVec<I, T>
 #[cfg(feature = "serde")]
 impl<'de, I: Idx, T: serde::de::Deserialize<'de>> serde::de::Deserialize<'de> for IndexVec<I, T> {
     fn deserialize<D: serde::de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        Vec::deserialize(deserializer).map(Self::from_vec)
+        let vec = Vec::deserialize(deserializer)?;
+        serde_check_len::<I, D::Error>(vec.len())?;
+        Ok(Self::from_vec(vec))
     }
 }
 
@@ -801,10 +1595,101 @@ impl<I: Idx, T: serde::ser::Serialize> serde::ser::Serialize for IndexBox<I, T>
 #[cfg(feature = "serde")]
 impl<'de, I: Idx, T: serde::de::Deserialize<'de>> serde::de::Deserialize<'de> for IndexBox<I, [T]> {
     fn deserialize<D: serde::de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        Box::<[T]>::deserialize(deserializer).map(Into::into)
+        let b = Box::<[T]>::deserialize(deserializer)?;
+        serde_check_len::<I, D::Error>(b.len())?;
+        Ok(b.into())
     }
 }
 
+// Like the length check in `IndexVec::from_vec`, but reports the failure as a
+// deserialization error rather than panicking.
+#[cfg(feature = "serde")]
+fn serde_check_len<I: Idx, E: serde::de::Error>(len: usize) -> Result<(), E> {
+    match I::try_from_usize(len) {
+        Some(_) => Ok(()),
+        None => Err(E::custom(format_args!(
+            "length {} is too large for the index type",
+            len
+        ))),
+    }
+}
+
+// Used by `define_index_type!` for types declared with a named field, which
+// are serialized as a struct with just that field.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub fn __serialize_named_index<S: serde::ser::Serializer>(
+    name: &'static str,
+    field: &'static str,
+    value: usize,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+    let mut s = serializer.serialize_struct(name, 1)?;
+    s.serialize_field(field, &value)?;
+    s.end()
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub fn __deserialize_named_index<'de, D: serde::de::Deserializer<'de>>(
+    name: &'static str,
+    fields: &'static [&'static str],
+    deserializer: D,
+) -> Result<usize, D::Error> {
+    use core::fmt;
+    use serde::de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+    // Deserializes a key, returning whether it's the field we want.
+    struct Key(&'static str);
+    impl<'de> DeserializeSeed<'de> for Key {
+        type Value = bool;
+        fn deserialize<D: serde::de::Deserializer<'de>>(self, d: D) -> Result<bool, D::Error> {
+            d.deserialize_identifier(self)
+        }
+    }
+    impl<'de> Visitor<'de> for Key {
+        type Value = bool;
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("field identifier")
+        }
+        fn visit_str<E: Error>(self, v: &str) -> Result<bool, E> {
+            Ok(v == self.0)
+        }
+        fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<bool, E> {
+            Ok(v == self.0.as_bytes())
+        }
+    }
+
+    struct Named(&'static str, &'static str);
+    impl<'de> Visitor<'de> for Named {
+        type Value = usize;
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "struct {}", self.0)
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
+            seq.next_element()?
+                .ok_or_else(|| A::Error::invalid_length(0, &self))
+        }
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<usize, A::Error> {
+            let mut value = None;
+            // As with `#[derive(Deserialize)]`, unknown fields are ignored.
+            while let Some(ours) = map.next_key_seed(Key(self.1))? {
+                if !ours {
+                    map.next_value::<IgnoredAny>()?;
+                } else if value.is_some() {
+                    return Err(A::Error::duplicate_field(self.1));
+                } else {
+                    value = Some(map.next_value()?);
+                }
+            }
+            value.ok_or_else(|| A::Error::missing_field(self.1))
+        }
+    }
+
+    deserializer.deserialize_struct(name, fields, Named(name, fields[0]))
+}
+
 #[cfg(feature = "rkyv")]
 impl<I: Idx, T: rkyv::Archive> rkyv::Archive for IndexVec<I, T> {
     type Archived = <Vec<T> as rkyv::Archive>::Archived;
@@ -21,9 +21,78 @@
 /// }
 /// ```
 ///
-/// Note that you can use other index types than `u32`, and you can set it to be
-/// `MyIndex(pub u32)` as well. Currently, the wrapped item be a tuple struct,
-/// however (patches welcome).
+/// Several index types can be declared in one invocation, each followed by its
+/// own (optional) configuration:
+///
+/// ```rust
+/// index_vec::define_index_type! {
+///     pub struct NodeIdx = u32;
+///     pub struct EdgeIdx = u32;
+///     DEBUG_FORMAT = "e{}";
+///     pub struct LabelIdx = u16;
+/// }
+/// # fn main() {
+/// assert_eq!(format!("{:?}", EdgeIdx::new(3)), "e3");
+/// # }
+/// ```
+///
+/// Note that you can use other index types than `u32`.
+///
+/// The wrapped integer is stored in a private field named `_raw`. If you'd like
+/// to use it directly in the module that defines the type (to construct,
+/// destructure or read it by a meaningful name), you can write the declaration
+/// as a struct with a single named field instead. The field is still private,
+/// but with the `serde` feature, the type is then serialized as a struct with
+/// that one field (e.g. `{"row": 5}` in JSON), rather than as a bare integer.
+/// Note that constructing the struct directly skips the `MAX_INDEX` check,
+/// like `from_raw_unchecked`.
+///
+/// ```rust
+/// index_vec::define_index_type! {
+///     pub struct RowIdx { row: u32 }
+///     MAX_INDEX = 1000;
+/// }
+/// # fn main() {
+/// let RowIdx { row } = RowIdx::new(5);
+/// assert_eq!(row, 5);
+/// assert_eq!(RowIdx { row: 6 }.row, 6);
+/// # }
+/// ```
+///
+/// The raw type can also be a type alias, which is the way to pick it based on
+/// the target (rather than duplicating the whole invocation under `#[cfg]`).
+/// Attributes on the struct, including `#[cfg_attr]`, are passed through.
+///
+/// ```rust
+/// #[cfg(target_pointer_width = "16")]
+/// type RawIdx = u16;
+/// #[cfg(not(target_pointer_width = "16"))]
+/// type RawIdx = u32;
+///
+/// index_vec::define_index_type! {
+///     #[cfg_attr(feature = "some-feature", derive(Default))]
+///     pub struct MyIndex = RawIdx;
+/// }
+/// # fn main() {
+/// assert_eq!(MyIndex::MAX_INDEX, RawIdx::max_value() as usize);
+/// # }
+/// ```
+///
+/// The raw type can't be wider than `usize` (so e.g. `u128` is rejected, as is
+/// `u64` on 32-bit targets), since converting it to a `usize` index would lose
+/// information.
+///
+/// ```rust,compile_fail
+/// index_vec::define_index_type! {
+///     // Error: the raw type can't be wider than `usize`.
+///     pub struct HashIdx = u128;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The generated type compares, orders, and hashes the same way as its `usize`
+/// index does. In particular, `MyIndex::new(n)` and `n` always hash to the same
+/// value, so hashes can be computed from either.
 ///
 /// ### Customization
 ///
@@ -94,6 +163,24 @@
 /// }
 /// ```
 ///
+/// #### `SENTINEL = <expr>;`
+///
+/// If provided, we'll generate `sentinel()`, which returns this expression,
+/// and `is_sentinel(self)`, which checks for it. This just designates one
+/// value as special (e.g. to mean "none"), it doesn't change `MAX_INDEX` or
+/// any of the checks.
+///
+/// ```rust
+/// index_vec::define_index_type! {
+///     pub struct MyIdx = u16;
+///     SENTINEL = MyIdx::from_raw_unchecked(u16::max_value());
+/// }
+/// # fn main() {
+/// assert!(MyIdx::sentinel().is_sentinel());
+/// assert!(!MyIdx::new(0).is_sentinel());
+/// # }
+/// ```
+///
 /// #### `DEBUG_FORMAT = <expr>;`
 ///
 /// By default we write the underlying integer out in a Debug implementation
@@ -152,6 +239,152 @@
 /// assert_eq!(as_u32, 5);
 /// # }
 /// ```
+///
+/// #### `NO_FROM_IMPLS = true;`
+///
+/// Don't implement any `From` conversions between your index type and `usize`
+/// (or the raw type). The `From<usize>` impl panics on overflow, which hides
+/// the fallibility, and may also conflict with conversions you'd like to write
+/// yourself. The inherent constructors (`new`, `from_usize`, `from_raw`, etc.)
+/// are still generated.
+///
+/// It's an error to combine this with `IMPL_RAW_CONVERSIONS`.
+///
+/// ```rust,compile_fail
+/// index_vec::define_index_type! {
+///     struct FooIdx = u32;
+///     NO_FROM_IMPLS = true;
+/// }
+///
+/// # fn main() {
+/// // Error: no `From<usize>` impl.
+/// let _ = FooIdx::from(5usize);
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// index_vec::define_index_type! {
+///     struct FooIdx = u32;
+///     NO_FROM_IMPLS = true;
+/// }
+///
+/// # fn main() {
+/// // Error: no `From<u32>` impl (nor `From<FooIdx> for u32`).
+/// let _ = FooIdx::from(5u32);
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// index_vec::define_index_type! {
+///     struct FooIdx = u32;
+///     // Error: these contradict each other.
+///     NO_FROM_IMPLS = true;
+///     IMPL_RAW_CONVERSIONS = true;
+/// }
+/// # fn main() {}
+/// ```
+///
+/// #### `RELATIVE_ARITHMETIC = true;`
+///
+/// Implement `Add`, `Sub`, `AddAssign` and `SubAssign` between two values of
+/// your index type, producing another one. This is off by default, as adding
+/// two positions together is usually a bug, but it's handy when your indices
+/// are really offsets (e.g. into some flat buffer).
+///
+/// Note: before version 0.2, these impls were generated for every index type.
+/// Code relying on them needs to add this option when upgrading.
+///
+/// ```rust
+/// index_vec::define_index_type! {
+///     struct OffsetIdx = u32;
+///     RELATIVE_ARITHMETIC = true;
+/// }
+///
+/// # fn main() {
+/// let mut o = OffsetIdx::new(3);
+/// o += OffsetIdx::new(4);
+/// assert_eq!(o, OffsetIdx::new(7));
+/// assert_eq!(o - OffsetIdx::new(2), OffsetIdx::new(5));
+/// # }
+/// ```
+///
+/// ```rust,compile_fail
+/// index_vec::define_index_type! {
+///     struct PosIdx = u32;
+/// }
+///
+/// # fn main() {
+/// // Error: no `Add<PosIdx>` impl without `RELATIVE_ARITHMETIC`.
+/// let _ = PosIdx::new(3) + PosIdx::new(4);
+/// # }
+/// ```
+///
+/// #### `PARSE = true;`
+///
+/// Implement `core::str::FromStr`, by parsing the raw type and then checking
+/// the result against `MAX_INDEX` (as `from_raw` would). Failures of either
+/// step are reported via [`ParseIdxError`](crate::ParseIdxError).
+///
+/// ```rust
+/// index_vec::define_index_type! {
+///     struct FooIdx = u8;
+///     MAX_INDEX = 100;
+///     PARSE = true;
+/// }
+///
+/// # fn main() {
+/// use index_vec::ParseIdxError;
+/// assert_eq!("5".parse::<FooIdx>(), Ok(FooIdx::new(5)));
+/// assert!(matches!("five".parse::<FooIdx>(), Err(ParseIdxError::Int(_))));
+/// assert_eq!("101".parse::<FooIdx>(), Err(ParseIdxError::TooLarge(101)));
+/// # }
+/// ```
+///
+/// #### `STRIDE_ARITHMETIC = true;`
+///
+/// Implement `Mul<usize>` for your index type (and `Mul<YourIndex>` for
+/// `usize`), for e.g. `row * width + col` in flattened 2D layouts. This is off
+/// by default, as multiplying a position by a length is usually a bug. As with
+/// the other arithmetic impls, the result is checked against `MAX_INDEX`
+/// (unless that check has been disabled).
+///
+/// ```rust
+/// index_vec::define_index_type! {
+///     struct CellIdx = u32;
+///     STRIDE_ARITHMETIC = true;
+/// }
+///
+/// # fn main() {
+/// let (row, width) = (CellIdx::new(3), 10);
+/// assert_eq!(row * width + 4, CellIdx::new(34));
+/// assert_eq!(width * row, CellIdx::new(30));
+/// # }
+/// ```
+///
+/// #### `IMPL_TRY_FROM = true;`
+///
+/// Implement `TryFrom<u64>` and `TryFrom<i64>` for your index type, for indices
+/// that arrive as wider (or signed) integers from elsewhere. Values which are
+/// negative or above `MAX_INDEX` are rejected with an
+/// [`IdxOverflowError`](crate::IdxOverflowError), regardless of
+/// `DISABLE_MAX_INDEX_CHECK`.
+///
+/// This can't be combined with `IMPL_RAW_CONVERSIONS` if the raw type is
+/// `u64`, as `From<u64>` already provides a `TryFrom<u64>` impl.
+///
+/// ```rust
+/// use core::convert::TryFrom;
+/// index_vec::define_index_type! {
+///     struct FooIdx = u16;
+///     IMPL_TRY_FROM = true;
+/// }
+///
+/// # fn main() {
+/// assert_eq!(FooIdx::try_from(5u64), Ok(FooIdx::new(5)));
+/// assert!(FooIdx::try_from(-1i64).is_err());
+/// assert!(FooIdx::try_from(70000u64).is_err());
+/// # }
+/// ```
 #[macro_export]
 macro_rules! define_index_type {
     // public api
@@ -163,26 +396,137 @@ macro_rules! define_index_type {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
-            @derives [#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]]
-            @decl [$v struct $type ($raw)]
+            @derives [#[derive(Copy, Clone, PartialEq, Eq)]]
+            @decl [$v struct $type ($raw) _raw]
+            @debug_fmt ["{}"]
+            @max [(<$raw>::max_value() as usize)]
+            @no_check_max [false]
+            @from_impls [(true false)]
+        }
+    };
+    // with a named field
+    (
+        $(#[$attrs:meta])*
+        $v:vis struct $type:ident { $field:ident : $raw:ident $(,)? }
+        $($CONFIG_NAME:ident = $value:expr;)* $(;)?
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [#[derive(Copy, Clone, PartialEq, Eq)]]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt ["{}"]
             @max [(<$raw>::max_value() as usize)]
             @no_check_max [false]
+            @from_impls [(true false)]
+        }
+    };
+    // several structs in one invocation
+    (
+        $(#[$attrs:meta])*
+        $v:vis struct $type:ident { $field:ident : $raw:ident $(,)? }
+        $($rest:tt)+
+    ) => {
+        $crate::__define_index_type_split!{
+            @head [$(#[$attrs])* $v struct $type { $field: $raw }]
+            $($rest)+
+        }
+    };
+    (
+        $(#[$attrs:meta])*
+        $v:vis struct $type:ident = $raw:ident;
+        $($rest:tt)+
+    ) => {
+        $crate::__define_index_type_split!{
+            @head [$(#[$attrs])* $v struct $type = $raw;]
+            $($rest)+
         }
     };
 }
 
+// Splits the options belonging to the first struct off of a multi-struct
+// `define_index_type!` invocation, and hands the rest back to it.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __define_index_type_split {
+    (@head [$($head:tt)*] $CONFIG_NAME:ident = $value:expr; $($rest:tt)*) => {
+        $crate::__define_index_type_split!{
+            @head [$($head)* $CONFIG_NAME = $value;]
+            $($rest)*
+        }
+    };
+    (@head [$($head:tt)*] ; $($rest:tt)*) => {
+        $crate::__define_index_type_split!{ @head [$($head)*] $($rest)* }
+    };
+    (@head [$($head:tt)*]) => {
+        $crate::define_index_type!{ $($head)* }
+    };
+    (@head [$($head:tt)*] $($rest:tt)+) => {
+        $crate::define_index_type!{ $($head)* }
+        $crate::define_index_type!{ $($rest)+ }
+    };
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! unknown_define_index_type_option {
     () => {};
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __internal_maybe_index_impl_from {
+    // The first flag is for the `usize` conversions (cleared by
+    // `NO_FROM_IMPLS`), the second for the raw ones (`IMPL_RAW_CONVERSIONS`).
+    ((true false), $type:ident, $raw:ident) => {
+        $crate::__internal_maybe_index_impl_from!(@usize $type);
+    };
+    ((true true), $type:ident, $raw:ident) => {
+        $crate::__internal_maybe_index_impl_from!(@usize $type);
+        $crate::__internal_maybe_index_impl_from!(@raw $type, $raw);
+    };
+    ((false false), $type:ident, $raw:ident) => {};
+    ((false true), $type:ident, $raw:ident) => {
+        compile_error!("define_index_type!: `NO_FROM_IMPLS` can't be combined with `IMPL_RAW_CONVERSIONS`");
+    };
+    (@usize $type:ident) => {
+        impl From<$type> for usize {
+            #[inline]
+            fn from(v: $type) -> usize {
+                v.index()
+            }
+        }
+
+        impl From<usize> for $type {
+            #[inline]
+            fn from(value: usize) -> Self {
+                $type::from_usize(value)
+            }
+        }
+    };
+    (@raw $type:ident, $raw:ident) => {
+        impl From<$type> for $raw {
+            #[inline]
+            fn from(v: $type) -> $raw {
+                v.raw()
+            }
+        }
+
+        impl From<$raw> for $type {
+            #[inline]
+            fn from(value: $raw) -> Self {
+                Self::from_raw(value)
+            }
+        }
+    };
+}
+
 #[cfg(feature = "serde")]
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __internal_maybe_index_impl_serde {
-    ($type:ident) => {
+    // The default field name: a bare integer.
+    ($type:ident, _raw) => {
         impl serde::ser::Serialize for $type {
             fn serialize<S: serde::ser::Serializer>(
                 &self,
@@ -196,7 +540,49 @@ macro_rules! __internal_maybe_index_impl_serde {
             fn deserialize<D: serde::de::Deserializer<'de>>(
                 deserializer: D,
             ) -> Result<Self, D::Error> {
-                usize::deserialize(deserializer).map(Self::from_usize)
+                let value = usize::deserialize(deserializer)?;
+                Self::try_from_usize(value).ok_or_else(|| {
+                    serde::de::Error::custom(format_args!(
+                        "index {} exceeds MAX_INDEX ({})",
+                        value,
+                        Self::MAX_INDEX,
+                    ))
+                })
+            }
+        }
+    };
+    // A field name given by the user: a struct with that one field.
+    ($type:ident, $field:ident) => {
+        impl serde::ser::Serialize for $type {
+            fn serialize<S: serde::ser::Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                $crate::__serialize_named_index(
+                    stringify!($type),
+                    stringify!($field),
+                    self.index(),
+                    serializer,
+                )
+            }
+        }
+
+        impl<'de> serde::de::Deserialize<'de> for $type {
+            fn deserialize<D: serde::de::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                let value = $crate::__deserialize_named_index(
+                    stringify!($type),
+                    &[stringify!($field)],
+                    deserializer,
+                )?;
+                Self::try_from_usize(value).ok_or_else(|| {
+                    serde::de::Error::custom(format_args!(
+                        "index {} exceeds MAX_INDEX ({})",
+                        value,
+                        Self::MAX_INDEX,
+                    ))
+                })
             }
         }
     };
@@ -206,7 +592,7 @@ macro_rules! __internal_maybe_index_impl_serde {
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __internal_maybe_index_impl_serde {
-    ($type:ident) => {};
+    ($type:ident, $field:ident) => {};
 }
 
 #[cfg(feature = "rkyv")]
@@ -269,19 +655,21 @@ macro_rules! __define_index_type_inner {
         @configs [(DISABLE_MAX_INDEX_CHECK; $no_check_max:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$_old_no_check_max:expr]
+        @from_impls [$from_impls:tt]
     ) => {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
             @derives [$(#[$derive])*]
-            @decl [$v struct $type ($raw)]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt [$dbg]
             @max [$max]
             @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
         }
     };
 
@@ -290,19 +678,21 @@ macro_rules! __define_index_type_inner {
         @configs [(MAX_INDEX; $new_max:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$cm:expr]
+        @from_impls [$from_impls:tt]
     ) => {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
             @derives [$(#[$derive])*]
-            @decl [$v struct $type ($raw)]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt [$dbg]
             @max [$new_max]
             @no_check_max [$cm]
+            @from_impls [$from_impls]
         }
     };
 
@@ -311,19 +701,21 @@ macro_rules! __define_index_type_inner {
         @configs [(DEFAULT; $default_expr:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
     ) => {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
             @derives [$(#[$derive])*]
-            @decl [$v struct $type ($raw)]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt [$dbg]
             @max [$max]
             @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
         }
         impl Default for $type {
             #[inline]
@@ -333,24 +725,62 @@ macro_rules! __define_index_type_inner {
         }
     };
 
+    // SENTINEL
+    (
+        @configs [(SENTINEL; $sentinel_expr:expr) $(($CONFIG_NAME:ident; $value:expr))*]
+        @attrs [$(#[$attrs:meta])*]
+        @derives [$(#[$derive:meta])*]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
+        @debug_fmt [$dbg:expr]
+        @max [$max:expr]
+        @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [$(#[$derive])*]
+            @decl [$v struct $type ($raw) $field]
+            @debug_fmt [$dbg]
+            @max [$max]
+            @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
+        }
+        impl $type {
+            /// Returns the value reserved as a sentinel for this index type.
+            #[inline]
+            $v fn sentinel() -> Self {
+                $sentinel_expr
+            }
+
+            /// Returns true if this is the sentinel value.
+            #[inline]
+            $v fn is_sentinel(self) -> bool {
+                self == Self::sentinel()
+            }
+        }
+    };
+
     // DEBUG_FORMAT
     (
         @configs [(DEBUG_FORMAT; $dbg:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$old_dbg:expr]
         @max [$max:expr]
         @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
     ) => {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
             @derives [$(#[$derive])*]
-            @decl [$v struct $type ($raw)]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt [$dbg]
             @max [$max]
             @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
         }
     };
 
@@ -359,19 +789,21 @@ macro_rules! __define_index_type_inner {
         @configs [(DISPLAY_FORMAT; $format:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
     ) => {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
             @derives [$(#[$derive])*]
-            @decl [$v struct $type ($raw)]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt [$dbg]
             @max [$max]
             @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
         }
 
         impl core::fmt::Display for $type {
@@ -386,34 +818,233 @@ macro_rules! __define_index_type_inner {
         @configs [(IMPL_RAW_CONVERSIONS; $val:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$no_check_max:expr]
+        @from_impls [($usize_impls:tt $raw_impls:tt)]
     ) => {
         $crate::__define_index_type_inner!{
             @configs [$(($CONFIG_NAME; $value))*]
             @attrs [$(#[$attrs])*]
             @derives [$(#[$derive])*]
-            @decl [$v struct $type ($raw)]
+            @decl [$v struct $type ($raw) $field]
             @debug_fmt [$dbg]
             @max [$max]
             @no_check_max [$no_check_max]
+            @from_impls [($usize_impls true)]
         }
         // Ensure they passed in true. This is... cludgey.
         const _: [(); 1] = [(); $val as usize];
+    };
+    // NO_FROM_IMPLS
+    (
+        @configs [(NO_FROM_IMPLS; $val:expr) $(($CONFIG_NAME:ident; $value:expr))*]
+        @attrs [$(#[$attrs:meta])*]
+        @derives [$(#[$derive:meta])*]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
+        @debug_fmt [$dbg:expr]
+        @max [$max:expr]
+        @no_check_max [$no_check_max:expr]
+        @from_impls [($usize_impls:tt $raw_impls:tt)]
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [$(#[$derive])*]
+            @decl [$v struct $type ($raw) $field]
+            @debug_fmt [$dbg]
+            @max [$max]
+            @no_check_max [$no_check_max]
+            @from_impls [(false $raw_impls)]
+        }
+        // Ensure they passed in true, same as IMPL_RAW_CONVERSIONS.
+        const _: [(); 1] = [(); $val as usize];
+    };
+    // RELATIVE_ARITHMETIC
+    (
+        @configs [(RELATIVE_ARITHMETIC; $val:expr) $(($CONFIG_NAME:ident; $value:expr))*]
+        @attrs [$(#[$attrs:meta])*]
+        @derives [$(#[$derive:meta])*]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
+        @debug_fmt [$dbg:expr]
+        @max [$max:expr]
+        @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [$(#[$derive])*]
+            @decl [$v struct $type ($raw) $field]
+            @debug_fmt [$dbg]
+            @max [$max]
+            @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
+        }
+        // Ensure they passed in true, same as IMPL_RAW_CONVERSIONS.
+        const _: [(); 1] = [(); $val as usize];
 
-        impl From<$type> for $raw {
+        impl core::ops::Add for $type {
+            type Output = $type;
             #[inline]
-            fn from(v: $type) -> $raw {
-                v.raw()
+            fn add(self, other: $type) -> $type {
+                $type::new(other.index() + self.index())
             }
         }
 
-        impl From<$raw> for $type {
+        impl core::ops::Sub for $type {
+            type Output = $type;
             #[inline]
-            fn from(value: $raw) -> Self {
-                Self::from_raw(value)
+            fn sub(self, other: $type) -> $type {
+                $type::new(self.index().wrapping_sub(other.index()))
+            }
+        }
+
+        impl core::ops::AddAssign for $type {
+            #[inline]
+            fn add_assign(&mut self, other: $type) {
+                *self = *self + other
+            }
+        }
+
+        impl core::ops::SubAssign for $type {
+            #[inline]
+            fn sub_assign(&mut self, other: $type) {
+                *self = *self - other;
+            }
+        }
+    };
+    // PARSE
+    (
+        @configs [(PARSE; $val:expr) $(($CONFIG_NAME:ident; $value:expr))*]
+        @attrs [$(#[$attrs:meta])*]
+        @derives [$(#[$derive:meta])*]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
+        @debug_fmt [$dbg:expr]
+        @max [$max:expr]
+        @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [$(#[$derive])*]
+            @decl [$v struct $type ($raw) $field]
+            @debug_fmt [$dbg]
+            @max [$max]
+            @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
+        }
+        // Ensure they passed in true, same as IMPL_RAW_CONVERSIONS.
+        const _: [(); 1] = [(); $val as usize];
+
+        impl core::str::FromStr for $type {
+            type Err = $crate::ParseIdxError;
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let raw: $raw = s.parse().map_err($crate::ParseIdxError::Int)?;
+                if Self::CHECKS_MAX_INDEX && (raw as usize) > Self::MAX_INDEX {
+                    return Err($crate::ParseIdxError::TooLarge(raw as usize));
+                }
+                Ok(Self::from_raw(raw))
+            }
+        }
+    };
+    // STRIDE_ARITHMETIC
+    (
+        @configs [(STRIDE_ARITHMETIC; $val:expr) $(($CONFIG_NAME:ident; $value:expr))*]
+        @attrs [$(#[$attrs:meta])*]
+        @derives [$(#[$derive:meta])*]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
+        @debug_fmt [$dbg:expr]
+        @max [$max:expr]
+        @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [$(#[$derive])*]
+            @decl [$v struct $type ($raw) $field]
+            @debug_fmt [$dbg]
+            @max [$max]
+            @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
+        }
+        // Ensure they passed in true, same as IMPL_RAW_CONVERSIONS.
+        const _: [(); 1] = [(); $val as usize];
+
+        impl core::ops::Mul<usize> for $type {
+            type Output = Self;
+            #[inline]
+            fn mul(self, other: usize) -> Self {
+                match self.index().checked_mul(other) {
+                    Some(v) => Self::new(v),
+                    None => {
+                        // The product doesn't even fit in a `usize`, so it
+                        // can't be wrapped and handed to `new` to check (it
+                        // might wrap back into range).
+                        if Self::CHECKS_MAX_INDEX {
+                            $crate::__max_check_fail(usize::MAX, Self::MAX_INDEX);
+                        }
+                        Self::from_usize_unchecked(self.index().wrapping_mul(other))
+                    }
+                }
+            }
+        }
+
+        impl core::ops::Mul<$type> for usize {
+            type Output = $type;
+            #[inline]
+            fn mul(self, other: $type) -> $type {
+                other * self
+            }
+        }
+    };
+    // IMPL_TRY_FROM
+    (
+        @configs [(IMPL_TRY_FROM; $val:expr) $(($CONFIG_NAME:ident; $value:expr))*]
+        @attrs [$(#[$attrs:meta])*]
+        @derives [$(#[$derive:meta])*]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
+        @debug_fmt [$dbg:expr]
+        @max [$max:expr]
+        @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
+    ) => {
+        $crate::__define_index_type_inner!{
+            @configs [$(($CONFIG_NAME; $value))*]
+            @attrs [$(#[$attrs])*]
+            @derives [$(#[$derive])*]
+            @decl [$v struct $type ($raw) $field]
+            @debug_fmt [$dbg]
+            @max [$max]
+            @no_check_max [$no_check_max]
+            @from_impls [$from_impls]
+        }
+        // Ensure they passed in true, same as IMPL_RAW_CONVERSIONS.
+        const _: [(); 1] = [(); $val as usize];
+
+        impl core::convert::TryFrom<u64> for $type {
+            type Error = $crate::IdxOverflowError;
+            #[inline]
+            fn try_from(value: u64) -> Result<Self, Self::Error> {
+                match <usize as core::convert::TryFrom<u64>>::try_from(value) {
+                    Ok(i) if i <= Self::MAX_INDEX => Ok(Self::from_usize(i)),
+                    _ => Err($crate::IdxOverflowError::__new()),
+                }
+            }
+        }
+
+        impl core::convert::TryFrom<i64> for $type {
+            type Error = $crate::IdxOverflowError;
+            #[inline]
+            fn try_from(value: i64) -> Result<Self, Self::Error> {
+                match <usize as core::convert::TryFrom<i64>>::try_from(value) {
+                    Ok(i) if i <= Self::MAX_INDEX => Ok(Self::from_usize(i)),
+                    _ => Err($crate::IdxOverflowError::__new()),
+                }
             }
         }
     };
@@ -422,10 +1053,11 @@ macro_rules! __define_index_type_inner {
         @configs [($other:ident; $format:expr) $(($CONFIG_NAME:ident; $value:expr))*]
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
     ) => {
         $crate::unknown_define_index_type_option!($other);
     };
@@ -434,16 +1066,24 @@ macro_rules! __define_index_type_inner {
         @configs []
         @attrs [$(#[$attrs:meta])*]
         @derives [$(#[$derive:meta])*]
-        @decl [$v:vis struct $type:ident ($raw:ident)]
+        @decl [$v:vis struct $type:ident ($raw:ident) $field:ident]
         @debug_fmt [$dbg:expr]
         @max [$max:expr]
         @no_check_max [$no_check_max:expr]
+        @from_impls [$from_impls:tt]
     ) => {
 
         $(#[$derive])*
         $(#[$attrs])*
         #[repr(transparent)]
-        $v struct $type { _raw: $raw }
+        $v struct $type { $field: $raw }
+
+        // Indices are converted to and from `usize` freely, which would be
+        // lossy for a raw type like `u128`, so refuse those up front.
+        const _: () = assert!(
+            core::mem::size_of::<$raw>() <= core::mem::size_of::<usize>(),
+            "define_index_type!: the raw type can't be wider than `usize`",
+        );
 
         impl $type {
             /// If `Self::CHECKS_MAX_INDEX` is true, we'll assert if trying to
@@ -455,6 +1095,12 @@ macro_rules! __define_index_type_inner {
             /// larger than MAX_INDEX?
             $v const CHECKS_MAX_INDEX: bool = !$no_check_max;
 
+            /// The index with the value 0.
+            $v const ZERO: Self = Self::from_usize_unchecked(0);
+
+            /// The largest valid index, e.g. the one with the value `MAX_INDEX`.
+            $v const MAX: Self = Self::from_usize_unchecked(Self::MAX_INDEX);
+
             /// Construct this index type from a usize. Alias for `from_usize`.
             #[inline(always)]
             $v fn new(value: usize) -> Self {
@@ -476,32 +1122,45 @@ macro_rules! __define_index_type_inner {
             /// Construct from a usize without any checks.
             #[inline(always)]
             $v const fn from_usize_unchecked(value: usize) -> Self {
-                Self { _raw: value as $raw }
+                Self { $field: value as $raw }
             }
 
             /// Construct from the underlying type without any checks.
             #[inline(always)]
             $v const fn from_raw_unchecked(raw: $raw) -> Self {
-                Self { _raw: raw }
+                Self { $field: raw }
             }
 
             /// Construct this index type from a usize.
             #[inline]
             $v fn from_usize(value: usize) -> Self {
                 Self::check_index(value as usize);
-                Self { _raw: value as $raw }
+                Self { $field: value as $raw }
             }
 
-            /// Get the wrapped index as a usize.
+            /// Construct this index type from a usize, returning `None` where
+            /// `from_usize` would panic.
+            #[inline]
+            $v fn try_from_usize(value: usize) -> Option<Self> {
+                if Self::CHECKS_MAX_INDEX && (value > Self::MAX_INDEX) {
+                    None
+                } else {
+                    Some(Self { $field: value as $raw })
+                }
+            }
+
+            /// Get the wrapped index as a usize. Unlike `Idx::index`, this can
+            /// be used in `const` contexts (and it takes priority over the
+            /// trait method, so `i.index()` always works there).
             #[inline(always)]
             $v const fn index(self) -> usize {
-                self._raw as usize
+                self.$field as usize
             }
 
             /// Get the wrapped index.
             #[inline(always)]
             $v const fn raw(self) -> $raw {
-                self._raw
+                self.$field
             }
 
             /// Asserts `v <= Self::MAX_INDEX` unless Self::CHECKS_MAX_INDEX is false.
@@ -512,6 +1171,46 @@ macro_rules! __define_index_type_inner {
                 }
             }
 
+            /// Returns `self + rhs`, or `None` if that would go past
+            /// `MAX_INDEX` (or the raw type's max, if checks are disabled).
+            #[inline]
+            $v fn checked_add(self, rhs: usize) -> Option<Self> {
+                let v = self.index().checked_add(rhs)?;
+                if v > Self::ARITHMETIC_MAX {
+                    None
+                } else {
+                    Some(Self { $field: v as $raw })
+                }
+            }
+
+            /// Returns `self - rhs`, or `None` if that would go below zero.
+            #[inline]
+            $v fn checked_sub(self, rhs: usize) -> Option<Self> {
+                let v = self.index().checked_sub(rhs)?;
+                Some(Self { $field: v as $raw })
+            }
+
+            /// Returns `self + rhs`, clamped to `MAX_INDEX` (or the raw type's max,
+            /// if checks are disabled).
+            #[inline]
+            $v fn saturating_add(self, rhs: usize) -> Self {
+                let v = self.index().saturating_add(rhs).min(Self::ARITHMETIC_MAX);
+                Self { $field: v as $raw }
+            }
+
+            /// Returns `self - rhs`, clamped to zero.
+            #[inline]
+            $v fn saturating_sub(self, rhs: usize) -> Self {
+                Self { $field: self.index().saturating_sub(rhs) as $raw }
+            }
+
+            // The largest value `checked_add` and `saturating_add` will produce.
+            const ARITHMETIC_MAX: usize = if Self::CHECKS_MAX_INDEX {
+                Self::MAX_INDEX
+            } else {
+                <$raw>::MAX as usize
+            };
+
             const _ENSURE_RAW_IS_UNSIGNED: [(); 0] = [(); <$raw>::MIN as usize];
         }
 
@@ -521,6 +1220,31 @@ macro_rules! __define_index_type_inner {
             }
         }
 
+        // Hashes exactly like the `usize` index does, so that the hash of
+        // `$type::new(n)` is the same as that of `n`.
+        impl core::hash::Hash for $type {
+            #[inline]
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.index().hash(state)
+            }
+        }
+
+        // Implemented by hand (rather than derived) so that ordering is
+        // always by index value, regardless of how the type is laid out.
+        impl core::cmp::Ord for $type {
+            #[inline]
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.index().cmp(&other.index())
+            }
+        }
+
+        impl core::cmp::PartialOrd for $type {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
         impl core::cmp::PartialOrd<usize> for $type {
             #[inline]
             fn partial_cmp(&self, other: &usize) -> Option<core::cmp::Ordering> {
@@ -609,63 +1333,25 @@ macro_rules! __define_index_type_inner {
             }
         }
 
-        impl core::ops::Add for $type {
-            type Output = $type;
-            #[inline]
-            fn add(self, other: $type) -> $type {
-                $type::new(other.index() + self.index())
-            }
-        }
-
-        impl core::ops::Sub for $type {
-            type Output = $type;
-            #[inline]
-            fn sub(self, other: $type) -> $type {
-                $type::new(self.index().wrapping_sub(other.index()))
-            }
-        }
-
-        impl core::ops::AddAssign for $type {
-            #[inline]
-            fn add_assign(&mut self, other: $type) {
-                *self = *self + other
-            }
-        }
-
-        impl core::ops::SubAssign for $type {
-            #[inline]
-            fn sub_assign(&mut self, other: $type) {
-                *self = *self - other;
-            }
-        }
-
         impl $crate::Idx for $type {
             #[inline]
             fn from_usize(value: usize) -> Self {
-                Self::from(value)
-            }
-
-            #[inline]
-            fn index(self) -> usize {
-                usize::from(self)
+                $type::from_usize(value)
             }
-        }
 
-        impl From<$type> for usize {
             #[inline]
-            fn from(v: $type) -> usize {
-                v.index()
+            fn try_from_usize(value: usize) -> Option<Self> {
+                $type::try_from_usize(value)
             }
-        }
 
-        impl From<usize> for $type {
             #[inline]
-            fn from(value: usize) -> Self {
-                $type::from_usize(value)
+            fn index(self) -> usize {
+                $type::index(self)
             }
         }
 
-        $crate::__internal_maybe_index_impl_serde!($type);
+        $crate::__internal_maybe_index_impl_from!($from_impls, $type, $raw);
+        $crate::__internal_maybe_index_impl_serde!($type, $field);
         $crate::__internal_maybe_index_impl_rkyv!($type);
     };
 }
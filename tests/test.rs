@@ -1,6 +1,8 @@
 #![allow(clippy::assertions_on_constants, clippy::eq_op)]
 
-use index_vec::{index_vec, IndexSlice, IndexVec};
+use index_vec::{
+    index_vec, IdxEnumerate, IndexSlice, IndexVec, IndexVecBuilder, IntoIndexVec, Len,
+};
 
 index_vec::define_index_type! {
     pub struct USize16 = usize;
@@ -28,6 +30,7 @@ index_vec::define_index_type! {
     DEBUG_FORMAT = "Test({:?})";
     DISPLAY_FORMAT = "foo {}";
     IMPL_RAW_CONVERSIONS = true;
+    RELATIVE_ARITHMETIC = true;
 }
 
 index_vec::define_index_type! {
@@ -567,3 +570,1824 @@ fn test_splits() {
     assert!(v.split_first_mut().is_none());
     assert!(v.split_last_mut().is_none());
 }
+
+#[test]
+fn test_chunk_descriptors() {
+    let v: IndexVec<IdxSz, i32> = index_vec![0, 1, 2, 3, 4];
+    let chunks: IndexVec<Idx16, core::ops::Range<IdxSz>> = v.chunk_descriptors(2);
+    assert_eq!(
+        chunks,
+        [
+            IdxSz::new(0)..IdxSz::new(2),
+            IdxSz::new(2)..IdxSz::new(4),
+            IdxSz::new(4)..IdxSz::new(5),
+        ]
+    );
+    assert_eq!(v[chunks[Idx16::new(2)].clone()], [4]);
+
+    let empty: IndexVec<IdxSz, i32> = index_vec![];
+    assert!(empty.chunk_descriptors::<Idx16>(2).is_empty());
+}
+
+#[test]
+fn test_from_array() {
+    let v: IndexVec<Idx32, i32> = [1, 2, 3].into();
+    assert_eq!(v, [1, 2, 3]);
+    assert_eq!(v[Idx32::new(2)], 3);
+
+    let e: IndexVec<Idx32, i32> = [].into();
+    assert!(e.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_from_array_too_long() {
+    let _: IndexVec<ZeroMax, i32> = [1, 2].into();
+}
+
+#[test]
+fn test_intern_consecutive() {
+    let mut v: IndexVec<Idx32, i32> = index_vec![1, 1, 2, 3, 3, 3, 1];
+    let map = v.intern_consecutive(|x| *x);
+    assert_eq!(v, [1, 2, 3, 1]);
+    assert_eq!(map, [0usize, 0, 1, 2, 2, 2, 3].map(Idx32::new));
+    for (orig, &canon) in map.iter_enumerated() {
+        assert_eq!(v[canon], [1, 1, 2, 3, 3, 3, 1][orig.index()]);
+    }
+
+    let mut e: IndexVec<Idx32, i32> = index_vec![];
+    assert!(e.intern_consecutive(|x| *x).is_empty());
+}
+
+#[test]
+fn test_search_insert_point() {
+    let b: &IndexSlice<IdxSz, [i32]> = IndexSlice::new(&[10, 20, 30]);
+    assert_eq!(b.search_insert_point(&5), IdxSz::new(0));
+    assert_eq!(b.search_insert_point(&20), IdxSz::new(1));
+    assert_eq!(b.search_insert_point(&25), IdxSz::new(2));
+    assert_eq!(b.search_insert_point(&35), IdxSz::new(3));
+
+    let e: &IndexSlice<IdxSz, [i32]> = IndexSlice::new(&[]);
+    assert_eq!(e.search_insert_point(&1), IdxSz::new(0));
+}
+
+#[test]
+fn test_const_get() {
+    fn second<const N: usize>(v: &IndexSlice<Idx32, [i32]>) -> i32 {
+        *v.const_get::<N>()
+    }
+    let v: IndexVec<Idx32, i32> = index_vec![10, 20, 30];
+    assert_eq!(*v.const_get::<0>(), 10);
+    assert_eq!(second::<1>(&v), 20);
+    assert_eq!(*v.const_get::<2>(), v[Idx32::from_usize_unchecked(2)]);
+}
+
+index_vec::define_index_type! {
+    pub struct NoFromImpls = u32;
+    NO_FROM_IMPLS = true;
+}
+
+// Would conflict with the generated impls if they weren't suppressed.
+impl From<usize> for NoFromImpls {
+    fn from(v: usize) -> Self {
+        Self::from_usize(v * 2)
+    }
+}
+
+impl From<NoFromImpls> for u32 {
+    fn from(v: NoFromImpls) -> u32 {
+        v.raw() + 1
+    }
+}
+
+#[test]
+fn test_no_from_impls() {
+    assert_eq!(NoFromImpls::from(3usize), 6usize);
+    assert_eq!(NoFromImpls::new(3), 3usize);
+    assert_eq!(u32::from(NoFromImpls::new(3)), 4);
+
+    let mut v: IndexVec<NoFromImpls, i32> = index_vec![1, 2];
+    let i = v.push(3);
+    assert_eq!(i, NoFromImpls::new(2));
+    assert_eq!(v[i], 3);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_iter_enumerated() {
+    use rayon::prelude::*;
+    let v: IndexVec<Idx32, u64> = (0..1000u64).collect();
+    let sum: u64 = v
+        .par_iter_enumerated()
+        .map(|(i, &x)| {
+            assert_eq!(i.index() as u64, x);
+            u64::from(i.raw()) * x
+        })
+        .sum();
+    assert_eq!(sum, (0..1000u64).map(|x| x * x).sum::<u64>());
+}
+
+#[test]
+fn test_strip_prefix_suffix() {
+    let v: IndexVec<Idx8, u8> = index_vec![0x7e, 1, 2, 3, 0x7f];
+    let body = v.strip_prefix(&[0x7e]).unwrap();
+    assert_eq!(body, &[1, 2, 3, 0x7f]);
+    assert_eq!(body[Idx8::new(0)], 1);
+    assert!(v.strip_prefix(&[0x7f]).is_none());
+    assert_eq!(v.strip_prefix(&[]).unwrap(), &v[..]);
+
+    let head = v.strip_suffix(&[3, 0x7f]).unwrap();
+    assert_eq!(head, &[0x7e, 1, 2]);
+    assert_eq!(head[Idx8::new(2)], v[Idx8::new(2)]);
+    assert!(v.strip_suffix(&[0x7e]).is_none());
+}
+
+#[test]
+fn test_remove_indices() {
+    let mut v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c', 'd', 'e'];
+    let mut mask: IndexVec<Idx32, bool> = index_vec![false; v.len()];
+    mask[Idx32::new(1)] = true;
+    mask[Idx32::new(3)] = true;
+    let remap = v.remove_indices(&mask);
+    assert_eq!(v, ['a', 'c', 'e']);
+    assert_eq!(
+        remap,
+        [
+            Some(Idx32::new(0)),
+            None,
+            Some(Idx32::new(1)),
+            None,
+            Some(Idx32::new(2)),
+        ]
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_remove_indices_wrong_len() {
+    let mut v: IndexVec<Idx32, char> = index_vec!['a', 'b'];
+    v.remove_indices(IndexSlice::new(&[true]));
+}
+
+index_vec::define_index_type! {
+    pub struct CharIdx = u32;
+}
+
+#[test]
+fn test_enumerate_idx() {
+    let v: Vec<(CharIdx, char)> = "héllo".chars().enumerate_idx().collect();
+    assert_eq!(v.len(), 5);
+    assert_eq!(v[1], (CharIdx::new(1), 'é'));
+    assert_eq!(v[4], (CharIdx::new(4), 'o'));
+}
+
+#[test]
+fn test_typed_ctors() {
+    let v = IndexVec::<Idx16, _>::typed(vec![1, 2, 3]);
+    assert_eq!(v[Idx16::new(1)], 2);
+
+    let w = vec!['a', 'b'].into_index_vec::<Idx8>();
+    assert_eq!(w.last_idx(), Idx8::new(1));
+}
+
+#[test]
+fn test_rev_enumerated_collect() {
+    let v: IndexVec<Idx32, i32> = index_vec![10, 20, 30];
+    let pairs: Vec<(Idx32, i32)> = v.rev_enumerated_collect();
+    assert_eq!(
+        pairs,
+        [
+            (Idx32::new(2), 30),
+            (Idx32::new(1), 20),
+            (Idx32::new(0), 10)
+        ]
+    );
+}
+
+#[test]
+fn test_idx_ordering() {
+    assert!(Idx32::new(1) < Idx32::new(2));
+    assert_eq!(Idx8::new(3).cmp(&Idx8::new(3)), core::cmp::Ordering::Equal);
+    let mut v = vec![Idx16::new(5), Idx16::new(1), Idx16::new(300), Idx16::new(2)];
+    v.sort();
+    assert_eq!(v, [1usize, 2, 5, 300].map(Idx16::new));
+    assert_eq!(v.iter().max(), Some(&Idx16::new(300)));
+}
+
+#[test]
+fn test_resize() {
+    let mut v: IndexVec<Idx8, u8> = index_vec![];
+    v.resize(255, 1);
+    assert_eq!(v.len(), 255);
+    v.resize_with(3, || 2);
+    assert_eq!(v, [1, 1, 1]);
+}
+
+#[test]
+#[should_panic]
+fn test_resize_past_max() {
+    let mut v: IndexVec<Idx8, u8> = index_vec![];
+    v.resize(1000, 0);
+}
+
+#[test]
+#[should_panic]
+fn test_resize_with_past_max() {
+    let mut v: IndexVec<Idx8, u8> = index_vec![];
+    v.resize_with(1000, || 0);
+}
+
+#[test]
+fn test_enumerated_range() {
+    let v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c', 'd', 'e'];
+    let got: Vec<(Idx32, &char)> = v.enumerated_range(Idx32::new(1)..Idx32::new(4)).collect();
+    assert_eq!(
+        got,
+        [
+            (Idx32::new(1), &'b'),
+            (Idx32::new(2), &'c'),
+            (Idx32::new(3), &'d'),
+        ]
+    );
+    assert_eq!(v.enumerated_range(..).count(), 5);
+    assert_eq!(
+        v.enumerated_range(Idx32::new(3)..).next_back(),
+        Some((Idx32::new(4), &'e'))
+    );
+    assert_eq!(v.enumerated_range(..=Idx32::new(1)).len(), 2);
+}
+
+#[test]
+fn test_sort_dedup() {
+    let mut v: IndexVec<Idx32, i32> = index_vec![3, 1, 2, 1, 3];
+    v.sort_dedup();
+    assert_eq!(v, [1, 2, 3]);
+
+    let mut v: IndexVec<Idx32, i32> = index_vec![3, 1, 2, 1, 3];
+    v.sort_unstable_dedup();
+    assert_eq!(v, [1, 2, 3]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_sparse() {
+    let mut map = std::collections::HashMap::new();
+    map.insert(Idx32::new(0), 'a');
+    map.insert(Idx32::new(3), 'd');
+    map.insert(Idx32::new(1), 'b');
+    let v = IndexVec::from_sparse(map, |_| '?');
+    assert_eq!(v, ['a', 'b', '?', 'd']);
+
+    let v: IndexVec<Idx32, usize> =
+        IndexVec::from_sparse(std::collections::HashMap::new(), |i: Idx32| i.index());
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_bounds() {
+    let v: IndexVec<Idx16, i32> = index_vec![5, 6, 7];
+    assert_eq!(v.bounds(), Some((Idx16::new(0), Idx16::new(2))));
+    let one: IndexVec<Idx16, i32> = index_vec![5];
+    assert_eq!(one.bounds(), Some((Idx16::new(0), Idx16::new(0))));
+    let empty: IndexVec<Idx16, i32> = index_vec![];
+    assert_eq!(empty.bounds(), None);
+}
+
+#[test]
+fn test_inclusive_range_at_max() {
+    // The largest `Idx8`-indexed vec has indices up to 254.
+    let mut v: IndexVec<Idx8, u8> = (0..255).collect();
+    let last = v.last_idx();
+    assert_eq!(last, Idx8::new(254));
+    assert_eq!(v[..=last].len(), 255);
+    assert_eq!(v[last..=last], [254]);
+    assert_eq!(v.get(..=last).map(|s| s.len()), Some(255));
+    assert_eq!(v.get_mut(Idx8::new(0)..=last).map(|s| s.len()), Some(255));
+    assert!(v.get(..=Idx8::new(255)).is_none());
+    assert!(v.get(Idx8::new(255)..=Idx8::new(255)).is_none());
+
+    // With checks disabled, index 255 itself is reachable.
+    let w: IndexVec<SmallUnchecked, u8> = (0..=255).collect();
+    let last = w.last_idx();
+    assert_eq!(last.raw(), 255);
+    assert_eq!(w[..=last].len(), 256);
+    assert_eq!(w[last..=last], [255]);
+    assert_eq!(w[SmallUnchecked::new(0)..=last].len(), 256);
+}
+
+#[test]
+#[should_panic]
+fn test_inclusive_range_past_end() {
+    let v: IndexVec<Idx8, u8> = (0..255).collect();
+    let _ = &v[..=Idx8::new(255)];
+}
+
+index_vec::define_index_type! {
+    pub struct NIdx = u32;
+    DEBUG_FORMAT = "N{}";
+}
+
+#[test]
+fn test_debug_format() {
+    assert_eq!(format!("{:?}", NIdx::new(7)), "N7");
+    assert_eq!(format!("{:?}", [NIdx::new(1), NIdx::new(2)]), "[N1, N2]");
+}
+
+#[test]
+fn test_enumerated_nth_skip() {
+    let mut v: IndexVec<Idx32, i32> = index_vec![10, 11, 12, 13, 14];
+    assert_eq!(v.iter_enumerated().nth(3), Some((Idx32::new(3), &13)));
+    assert_eq!(v.iter_enumerated().nth(5), None);
+    let skipped: Vec<(Idx32, &i32)> = v.iter_enumerated().skip(2).collect();
+    assert_eq!(skipped[0], (Idx32::new(2), &12));
+    assert_eq!(skipped.len(), 3);
+    assert_eq!(v.iter_enumerated().rev().nth(1), Some((Idx32::new(3), &13)));
+
+    let mut it = v.iter_enumerated();
+    assert_eq!(it.nth(1), Some((Idx32::new(1), &11)));
+    assert_eq!(it.nth(1), Some((Idx32::new(3), &13)));
+
+    assert_eq!(
+        v.iter_mut_enumerated().nth(4),
+        Some((Idx32::new(4), &mut 14))
+    );
+    let skipped: Vec<Idx32> = v
+        .clone()
+        .into_iter_enumerated()
+        .skip(3)
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(skipped, [Idx32::new(3), Idx32::new(4)]);
+}
+
+#[test]
+fn test_slice_as_ref() {
+    fn sum<S: AsRef<[u8]> + ?Sized>(s: &S) -> u32 {
+        s.as_ref().iter().map(|&b| u32::from(b)).sum()
+    }
+    fn zero<S: AsMut<[u8]> + ?Sized>(s: &mut S) {
+        s.as_mut().iter_mut().for_each(|b| *b = 0);
+    }
+    let mut v: IndexVec<Idx8, u8> = index_vec![1, 2, 3];
+    let s: &IndexSlice<Idx8, [u8]> = &v[Idx8::new(1)..];
+    assert_eq!(sum(s), 5);
+    zero(&mut v[..Idx8::new(2)]);
+    assert_eq!(v, [0, 0, 3]);
+}
+
+#[test]
+fn test_split_off_enumerated() {
+    let mut v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c', 'd', 'e'];
+    let tail = v.split_off_enumerated(Idx32::new(2));
+    assert_eq!(v, ['a', 'b']);
+    assert_eq!(
+        tail,
+        [
+            (Idx32::new(2), 'c'),
+            (Idx32::new(3), 'd'),
+            (Idx32::new(4), 'e'),
+        ]
+    );
+    assert!(v.split_off_enumerated(Idx32::new(2)).is_empty());
+}
+
+#[test]
+fn test_equal_range_by_key() {
+    let v: IndexVec<IdxSz, (i32, char)> =
+        index_vec![(1, 'a'), (2, 'b'), (2, 'c'), (2, 'd'), (3, 'e')];
+    assert_eq!(
+        v.equal_range_by_key(&2, |p| p.0),
+        IdxSz::new(1)..IdxSz::new(4)
+    );
+    assert_eq!(
+        v.equal_range_by_key(&3, |p| p.0),
+        IdxSz::new(4)..IdxSz::new(5)
+    );
+    assert_eq!(
+        v.equal_range_by_key(&0, |p| p.0),
+        IdxSz::new(0)..IdxSz::new(0)
+    );
+    assert_eq!(
+        v.equal_range_by_key(&9, |p| p.0),
+        IdxSz::new(5)..IdxSz::new(5)
+    );
+}
+
+#[test]
+fn test_assert_len_idx() {
+    let v: IndexVec<Idx32, i32> = index_vec![1, 2, 3];
+    v.assert_len_idx(Idx32::new(3));
+}
+
+#[test]
+#[should_panic(expected = "IndexVec length mismatch")]
+fn test_assert_len_idx_mismatch() {
+    let v: IndexVec<Idx32, i32> = index_vec![1, 2, 3];
+    v.assert_len_idx(Idx32::new(2));
+}
+
+#[test]
+fn test_try_for_each_enumerated() {
+    let v: IndexVec<Idx32, i32> = index_vec![1, 2, -3, 4, -5];
+    let mut seen = vec![];
+    let res = v.try_for_each_enumerated(|i, &x| {
+        if x < 0 {
+            return Err(i);
+        }
+        seen.push(x);
+        Ok(())
+    });
+    assert_eq!(res, Err(Idx32::new(2)));
+    assert_eq!(seen, [1, 2]);
+
+    let ok: Result<(), Idx32> = v[..Idx32::new(2)].try_for_each_enumerated(|_, _| Ok(()));
+    assert_eq!(ok, Ok(()));
+}
+
+#[test]
+fn test_idx_hash_matches_usize() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    fn hash_of<H: Hash>(h: H) -> u64 {
+        let mut s = DefaultHasher::new();
+        h.hash(&mut s);
+        s.finish()
+    }
+    assert_eq!(hash_of(Idx32::new(5)), hash_of(5usize));
+    assert_eq!(hash_of(Idx8::new(200)), hash_of(200usize));
+    assert_eq!(hash_of(IdxSz::new(12345)), hash_of(12345usize));
+    assert_ne!(hash_of(Idx32::new(5)), hash_of(6usize));
+}
+
+#[test]
+fn test_reserve_tracked() {
+    let mut v: IndexVec<Idx32, i32> = IndexVec::with_capacity(4);
+    v.push(1);
+    assert!(!v.reserve_tracked(3));
+    assert!(!v.reserve_tracked(0));
+    assert!(v.reserve_tracked(100));
+    assert!(v.raw.capacity() >= 101);
+}
+
+#[test]
+fn test_map_collect() {
+    let v: IndexVec<Idx32, usize> = index_vec![5, 6, 7];
+    let products: IndexVec<Idx32, usize> = v.map_collect(|i, &x| i.index() * x);
+    assert_eq!(products, [0, 6, 14]);
+    let tail = v[Idx32::new(1)..].map_collect(|i, _| i);
+    assert_eq!(tail, [Idx32::new(0), Idx32::new(1)]);
+}
+
+#[test]
+fn test_builder_push_expect() {
+    let mut b = IndexVecBuilder::<Idx32, u8>::new();
+    b.push_expect(Idx32::new(0), 10);
+    b.push_expect(Idx32::new(1), 20);
+    assert_eq!(b.finish(), [10, 20]);
+}
+
+#[test]
+#[should_panic]
+fn test_builder_push_expect_mismatch() {
+    let mut b = IndexVecBuilder::<Idx32, u8>::new();
+    b.push_expect(Idx32::new(0), 10);
+    b.push_expect(Idx32::new(2), 20);
+}
+
+#[test]
+fn test_usize_range_index() {
+    let mut v: IndexVec<Idx32, u32> = index_vec![0, 1, 2, 3, 4, 5];
+    assert_eq!(&v[2usize..4usize], &[2, 3]);
+    assert_eq!(&v[4usize..], &[4, 5]);
+    assert_eq!(&v[..2usize], &[0, 1]);
+    assert_eq!(&v[1usize..=2usize], &[1, 2]);
+    assert_eq!(&v[..=1usize], &[0, 1]);
+    assert_eq!(v.get(5usize..7usize), None);
+    v[2usize..4usize].raw.copy_from_slice(&[7, 8]);
+    assert_eq!(v, [0, 1, 7, 8, 4, 5]);
+}
+
+#[test]
+fn test_index_range() {
+    let v: IndexVec<Idx32, u32> = index_vec![3, 4, 5];
+    assert_eq!(v.index_range(), Idx32::new(0)..Idx32::new(3));
+    assert_eq!(&v[v.index_range()], &v[..]);
+    let e: IndexVec<Idx32, u32> = IndexVec::new();
+    assert!(e.index_range().is_empty());
+}
+
+index_vec::define_index_type! {
+    pub struct OffsetIdx = u32;
+    RELATIVE_ARITHMETIC = true;
+}
+
+#[test]
+fn test_relative_arithmetic() {
+    let lens: IndexVec<OffsetIdx, u32> = index_vec![3, 0, 5, 2];
+    let mut starts: IndexVec<OffsetIdx, OffsetIdx> = IndexVec::new();
+    let mut offset = OffsetIdx::new(0);
+    for &len in &lens {
+        starts.push(offset);
+        offset += OffsetIdx::from_raw(len);
+    }
+    assert_eq!(starts, [0usize, 3, 3, 8].map(OffsetIdx::new));
+    assert_eq!(offset, OffsetIdx::new(10));
+    assert_eq!(offset - starts[OffsetIdx::new(3)], OffsetIdx::new(2));
+    offset -= OffsetIdx::new(4);
+    assert_eq!(offset + OffsetIdx::new(1), OffsetIdx::new(7));
+}
+
+fn assert_fused<It: core::iter::FusedIterator>(it: It) -> It {
+    it
+}
+
+#[test]
+fn test_iter_fused_and_rev() {
+    let mut v: IndexVec<Idx32, u32> = index_vec![10, 20, 30];
+
+    let mut it = assert_fused(v.iter_enumerated());
+    assert_eq!(it.by_ref().count(), 3);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+
+    let mut it = assert_fused(v.indices());
+    assert_eq!(it.next_back(), Some(Idx32::new(2)));
+    assert_eq!(it.by_ref().count(), 2);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+
+    let mut it = assert_fused(v.enumerated_range(Idx32::new(1)..));
+    assert_eq!(it.by_ref().count(), 2);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+
+    let mut it = assert_fused(v.iter_mut_enumerated());
+    assert_eq!(it.by_ref().count(), 3);
+    assert!(it.next().is_none());
+    assert!(it.next().is_none());
+
+    let rev: Vec<_> = v.iter_enumerated().rev().collect();
+    assert_eq!(
+        rev,
+        [
+            (Idx32::new(2), &30),
+            (Idx32::new(1), &20),
+            (Idx32::new(0), &10)
+        ]
+    );
+    let rev: Vec<_> = v.indices().rev().collect();
+    assert_eq!(rev, [Idx32::new(2), Idx32::new(1), Idx32::new(0)]);
+    for (i, x) in v.iter_mut_enumerated().rev() {
+        *x += i.raw();
+    }
+    assert_eq!(v, [10, 21, 32]);
+
+    let mut it = assert_fused(v.clone().into_iter_enumerated());
+    assert_eq!(it.next_back(), Some((Idx32::new(2), 32)));
+    assert_eq!(it.by_ref().count(), 2);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+
+    // Like `drain(r).enumerate()`, these are numbered from the start of `r`.
+    let drained: Vec<_> = v.drain_enumerated(Idx32::new(1)..).rev().collect();
+    assert_eq!(drained, [(Idx32::new(1), 32), (Idx32::new(0), 21)]);
+}
+
+#[test]
+fn test_get_opt() {
+    // A little linked list: each item holds the index of the next one.
+    let mut next: IndexVec<Idx32, Option<Idx32>> =
+        index_vec![Some(Idx32::new(2)), None, Some(Idx32::new(1))];
+    let mut at = Some(Idx32::new(0));
+    let mut visited = vec![];
+    while let Some(&n) = next.get_opt(at) {
+        visited.push(at.unwrap());
+        at = n;
+    }
+    assert_eq!(visited, [Idx32::new(0), Idx32::new(2), Idx32::new(1)]);
+
+    assert_eq!(next.get_opt(None), None);
+    assert_eq!(next.get_opt(Some(Idx32::new(3))), None);
+    assert_eq!(next.get_opt_mut(None), None);
+    assert_eq!(next.get_opt_mut(Some(Idx32::new(3))), None);
+    *next.get_opt_mut(Some(Idx32::new(1))).unwrap() = Some(Idx32::new(0));
+    assert_eq!(next[Idx32::new(1)], Some(Idx32::new(0)));
+}
+
+#[test]
+fn test_canonicalize_by_key() {
+    let mut v: IndexVec<Idx32, i32> = index_vec![1, -1, 2, 3, -3, 3, 1];
+    let table = v.canonicalize_by_key(|x| x.abs());
+    // Unlike `intern_consecutive`, the trailing `1` is folded into the first.
+    assert_eq!(v, [1, 2, 3]);
+    assert_eq!(table.len(), 7);
+    assert_eq!(table, [0usize, 0, 1, 2, 2, 2, 0].map(Idx32::new));
+    let old: IndexVec<Idx32, i32> = index_vec![1, -1, 2, 3, -3, 3, 1];
+    for (o, &n) in table.iter_enumerated() {
+        assert_eq!(old[o].abs(), v[n].abs());
+    }
+
+    let mut c = old.clone();
+    assert_eq!(
+        c.intern_consecutive(|x| x.abs()),
+        [0usize, 0, 1, 2, 2, 2, 3].map(Idx32::new)
+    );
+    assert_eq!(c, [1, 2, 3, 1]);
+
+    let mut e: IndexVec<Idx32, i32> = IndexVec::new();
+    assert!(e.canonicalize_by_key(|x| *x).is_empty());
+}
+
+#[test]
+fn test_slice_ref_into_iter() {
+    let mut v: IndexVec<Idx32, u32> = index_vec![1, 2, 3, 4];
+    let (a, b): (&IndexSlice<Idx32, [u32]>, _) = v.split_at(Idx32::new(1));
+    let mut sum = 0;
+    for x in a {
+        sum += x;
+    }
+    for x in b {
+        sum += x * 10;
+    }
+    assert_eq!(sum, 91);
+
+    let (_, b) = v.split_at_mut(Idx32::new(2));
+    for x in b {
+        *x = 0;
+    }
+    assert_eq!(v, [1, 2, 0, 0]);
+}
+
+#[test]
+fn test_zero_and_max_consts() {
+    assert_eq!(Idx32::ZERO.index(), 0);
+    assert_eq!(Idx32::MAX.index(), Idx32::MAX_INDEX);
+    assert_eq!(SmallCheckedEarly::MAX.index(), 0x7f);
+    assert_eq!(USize16::MAX.index(), u16::MAX as usize);
+    assert_eq!(ZeroMax::MAX, ZeroMax::ZERO);
+    // Usable in const contexts, e.g. as a sentinel.
+    const NONE: Idx8 = Idx8::MAX;
+    assert_eq!(NONE.raw(), u8::MAX);
+}
+
+#[test]
+fn test_into_btreemap() {
+    let v: IndexVec<Idx32, char> = index_vec!['c', 'a', 'b'];
+    let m = v.into_btreemap();
+    assert_eq!(m.len(), 3);
+    let items: Vec<_> = m.into_iter().collect();
+    assert_eq!(
+        items,
+        [
+            (Idx32::new(0), 'c'),
+            (Idx32::new(1), 'a'),
+            (Idx32::new(2), 'b')
+        ]
+    );
+}
+
+index_vec::define_index_type! {
+    pub struct MultiA = u32;
+    /// A doc comment on the second one.
+    pub struct MultiB = u16;
+    DEBUG_FORMAT = "B{}";
+    MAX_INDEX = 100;
+    ;
+    struct MultiC = usize;
+    RELATIVE_ARITHMETIC = true;
+}
+
+#[test]
+fn test_define_multiple() {
+    let a: IndexVec<MultiA, u8> = index_vec![1, 2];
+    assert_eq!(a[MultiA::new(1)], 2);
+    assert_eq!(format!("{:?}", MultiB::new(4)), "B4");
+    assert_eq!(MultiB::MAX_INDEX, 100);
+    assert_eq!(MultiA::MAX_INDEX, u32::MAX as usize);
+    assert_eq!(MultiC::new(1) + MultiC::new(2), MultiC::new(3));
+}
+
+#[test]
+fn test_get_or_empty() {
+    let v: IndexVec<Idx32, u32> = index_vec![0, 1, 2, 3];
+    assert_eq!(v.get_or_empty(Idx32::new(1)..Idx32::new(3)), &[1, 2]);
+    assert_eq!(v.get_or_empty(Idx32::new(2)..Idx32::new(10)), &[2, 3]);
+    assert_eq!(v.get_or_empty(..=Idx32::new(10)), &[0, 1, 2, 3]);
+    assert!(v.get_or_empty(Idx32::new(5)..).is_empty());
+    assert!(v.get_or_empty(Idx32::new(3)..Idx32::new(1)).is_empty());
+    assert!(v.get_or_empty(Idx32::new(6)..Idx32::new(9)).is_empty());
+}
+
+#[test]
+fn test_peeking_indices() {
+    let v: IndexVec<Idx32, u32> = index_vec![7, 8];
+    let mut it = v.peeking_indices();
+    assert_eq!(it.peek().map(|&(i, _)| i), Some(Idx32::new(0)));
+    assert_eq!(it.peek().map(|&(i, _)| i), Some(Idx32::new(0)));
+    assert_eq!(it.next(), Some((Idx32::new(0), &7)));
+    assert_eq!(it.next_if(|&(_, &x)| x == 9), None);
+    assert_eq!(it.next(), Some((Idx32::new(1), &8)));
+    assert_eq!(it.peek(), None);
+}
+
+#[test]
+fn test_filled_default() {
+    let v: IndexVec<Idx32, Vec<u8>> = IndexVec::filled_default(4);
+    assert_eq!(v.len(), 4);
+    assert!(v.iter().all(|x| x.is_empty()));
+    let v: IndexVec<Idx32, u32> = IndexVec::filled_default(0);
+    assert!(v.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_filled_default_too_long() {
+    let _: IndexVec<ZeroMax, u32> = IndexVec::filled_default(2);
+}
+
+#[test]
+fn test_search_hinted() {
+    // A tiny LCG, so that the queries are "random" but reproducible.
+    let mut state = 0x2545_f491_u32;
+    let mut rand = move |n: u32| {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (state >> 8) % n
+    };
+    for len in [0, 1, 2, 3, 7, 50, 200] {
+        let mut v: IndexVec<Idx32, u32> = (0..len).map(|_| rand(400)).collect();
+        v.sort();
+        for _ in 0..300 {
+            let x = rand(420);
+            let hint = Idx32::new(rand(len + 3) as usize);
+            let expected = v.binary_search(&x);
+            match v.search_hinted(&x, hint) {
+                Ok(i) => {
+                    assert!(expected.is_ok());
+                    assert_eq!(v[i], x);
+                }
+                Err(i) => assert_eq!(Err(i), expected),
+            }
+        }
+    }
+    let v: IndexVec<Idx32, u32> = (0..100).map(|i| i * 2).collect();
+    for hint in [0, 10, 50, 99, 150] {
+        assert_eq!(v.search_hinted(&64, Idx32::new(hint)), Ok(Idx32::new(32)));
+        assert_eq!(v.search_hinted(&65, Idx32::new(hint)), Err(Idx32::new(33)));
+        assert_eq!(
+            v.search_hinted(&500, Idx32::new(hint)),
+            Err(Idx32::new(100))
+        );
+    }
+}
+
+#[test]
+fn test_from_enumerated() {
+    let v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c'];
+    let w = IndexVec::from_enumerated(v.iter_enumerated().map(|(i, &c)| (i, c)));
+    assert_eq!(v, w);
+    let w = IndexVec::try_from_enumerated(v.clone().into_iter_enumerated());
+    assert_eq!(w, Ok(v));
+
+    let gap = vec![(Idx32::new(0), 'a'), (Idx32::new(2), 'c')];
+    assert_eq!(IndexVec::try_from_enumerated(gap), Err(Idx32::new(2)));
+    let swapped = vec![(Idx32::new(1), 'b'), (Idx32::new(0), 'a')];
+    assert_eq!(IndexVec::try_from_enumerated(swapped), Err(Idx32::new(1)));
+    let empty: Vec<(Idx32, char)> = vec![];
+    assert_eq!(IndexVec::try_from_enumerated(empty), Ok(IndexVec::new()));
+}
+
+#[test]
+#[should_panic(expected = "expected index 1, got 2")]
+fn test_from_enumerated_gap() {
+    let _ = IndexVec::from_enumerated(vec![(Idx32::new(0), 'a'), (Idx32::new(2), 'c')]);
+}
+
+index_vec::define_index_type! {
+    pub struct ParseIdx = u8;
+    MAX_INDEX = 200;
+    PARSE = true;
+}
+
+#[test]
+fn test_parse() {
+    use index_vec::ParseIdxError;
+    assert_eq!("5".parse::<ParseIdx>(), Ok(ParseIdx::new(5)));
+    assert_eq!("200".parse::<ParseIdx>(), Ok(ParseIdx::new(200)));
+    assert!(matches!(
+        "five".parse::<ParseIdx>(),
+        Err(ParseIdxError::Int(_))
+    ));
+    // Out of range for `MAX_INDEX`, and for the raw type.
+    assert_eq!("201".parse::<ParseIdx>(), Err(ParseIdxError::TooLarge(201)));
+    assert!(matches!(
+        "300".parse::<ParseIdx>(),
+        Err(ParseIdxError::Int(_))
+    ));
+    assert_eq!(
+        ParseIdxError::TooLarge(201).to_string(),
+        "index 201 is too large"
+    );
+}
+
+#[test]
+fn test_rotate_left_remap() {
+    let orig: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c', 'd', 'e'];
+    let mut v = orig.clone();
+    let remap = v.rotate_left_remap(Idx32::new(2));
+    assert_eq!(v, ['c', 'd', 'e', 'a', 'b']);
+    let refs = [Idx32::new(0), Idx32::new(1), Idx32::new(2), Idx32::new(4)];
+    for &old in &refs {
+        assert_eq!(v[remap(old)], orig[old]);
+    }
+    assert_eq!(remap(Idx32::new(0)), Idx32::new(3));
+    assert_eq!(remap(Idx32::new(4)), Idx32::new(2));
+
+    let mut e: IndexVec<Idx32, char> = IndexVec::new();
+    let _ = e.rotate_left_remap(Idx32::new(0));
+}
+
+#[test]
+fn test_flat_enumerated() {
+    let v: IndexVec<Idx32, Vec<u8>> = index_vec![vec![1, 2], vec![], vec![3]];
+    let pairs: Vec<_> = v.flat_enumerated(|x| &x[..]).collect();
+    assert_eq!(
+        pairs,
+        [
+            (Idx32::new(0), &1),
+            (Idx32::new(0), &2),
+            (Idx32::new(2), &3)
+        ]
+    );
+}
+
+#[test]
+fn test_clear_and_shrink() {
+    let mut v: IndexVec<Idx32, u64> = (0..1000).collect();
+    assert!(v.raw.capacity() >= 1000);
+    v.clear_and_shrink(16);
+    assert!(v.is_empty());
+    assert!(v.raw.capacity() <= 16);
+    // Doesn't grow it, either.
+    let mut v: IndexVec<Idx32, u64> = IndexVec::with_capacity(4);
+    v.clear_and_shrink(100);
+    assert!(v.raw.capacity() < 100);
+}
+
+#[test]
+fn test_collect_indices_where() {
+    let v: IndexVec<Idx32, u32> = index_vec![1, 4, 6, 7, 8];
+    let evens: IndexVec<Idx16, Idx32> = v.collect_indices_where(|x| x % 2 == 0);
+    assert_eq!(evens, [1usize, 2, 4].map(Idx32::new));
+    assert_eq!(v[evens[Idx16::new(1)]], 6);
+
+    let all: IndexVec<Idx16, Idx32> = v.collect_indices_where(|_| true);
+    assert_eq!(all.len(), v.len());
+    // Presized, so no reallocation was needed.
+    assert_eq!(all.raw.capacity(), v.len());
+}
+
+index_vec::define_index_type! {
+    pub struct GridIdx = u8;
+    STRIDE_ARITHMETIC = true;
+}
+
+#[test]
+fn test_stride_arithmetic() {
+    let width = 16;
+    let row = GridIdx::new(3);
+    assert_eq!(row * width + 5, GridIdx::new(53));
+    assert_eq!(width * row + 5, GridIdx::new(53));
+    assert_eq!(GridIdx::new(0) * width, GridIdx::new(0));
+}
+
+#[test]
+#[should_panic]
+fn test_stride_arithmetic_overflow() {
+    let _ = GridIdx::new(16) * 16;
+}
+
+#[test]
+#[should_panic(expected = "index overflow")]
+fn test_stride_arithmetic_overflow_usize() {
+    // The product wraps around `usize` to 0, which is in range for `GridIdx`.
+    let _ = GridIdx::new(128) * (1usize << (usize::BITS - 7));
+}
+
+#[test]
+#[should_panic(expected = "index overflow")]
+fn test_stride_arithmetic_overflow_usize_rhs() {
+    // Wraps to 2.
+    let _ = (usize::MAX / 3 + 1) * GridIdx::new(3);
+}
+
+#[test]
+fn test_as_slice_of_cells() {
+    let mut v: IndexVec<Idx32, u32> = index_vec![1, 2, 3];
+    let cells = v.as_slice_of_cells();
+    let (a, b) = (&cells[Idx32::new(0)], &cells[Idx32::new(2)]);
+    a.set(a.get() + b.get());
+    b.set(a.get() * 10);
+    cells[Idx32::new(1)].swap(&cells[Idx32::new(0)]);
+    assert_eq!(v, [2, 4, 40]);
+}
+
+#[test]
+fn test_iter_enumerated_zip() {
+    let mut names: IndexVec<Idx32, &str> = index_vec!["a", "b", "c"];
+    let mut ages: IndexVec<Idx32, u32> = index_vec![30, 40, 50];
+    let joined: Vec<_> = names.iter_enumerated_zip(&ages).collect();
+    assert_eq!(
+        joined,
+        [
+            (Idx32::new(0), &"a", &30),
+            (Idx32::new(1), &"b", &40),
+            (Idx32::new(2), &"c", &50)
+        ]
+    );
+    for (i, name, age) in names.iter_enumerated_zip_mut(&mut ages) {
+        *age += i.raw();
+        *name = "z";
+    }
+    assert_eq!(ages, [30, 41, 52]);
+    assert_eq!(names, ["z"; 3]);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "length mismatch")]
+fn test_iter_enumerated_zip_mismatch() {
+    let a: IndexVec<Idx32, u32> = index_vec![1, 2, 3];
+    let b: IndexVec<Idx32, u32> = index_vec![1, 2];
+    let _ = a.iter_enumerated_zip(&b);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_io_write() {
+    use std::io::Write;
+    let mut buf: IndexVec<Idx32, u8> = IndexVec::new();
+    let s = "ab";
+    write!(buf, "{}-{}", 12, s).unwrap();
+    buf.flush().unwrap();
+    assert_eq!(buf.len(), 5);
+    assert_eq!(buf[Idx32::new(0)], b'1');
+    assert_eq!(buf[Idx32::new(2)], b'-');
+    assert_eq!(buf.last(), Some(&b'b'));
+}
+
+index_vec::define_index_type! {
+    pub struct WideIdx = u32;
+    MAX_INDEX = 1000;
+    IMPL_TRY_FROM = true;
+}
+
+#[test]
+fn test_try_from_wide() {
+    use core::convert::TryFrom;
+    use index_vec::IdxOverflowError;
+    assert_eq!(WideIdx::try_from(7u64), Ok(WideIdx::new(7)));
+    assert_eq!(WideIdx::try_from(1000i64), Ok(WideIdx::new(1000)));
+    assert!(WideIdx::try_from(-1i64).is_err());
+    assert!(WideIdx::try_from(i64::MIN).is_err());
+    assert!(WideIdx::try_from(1001u64).is_err());
+    let err: IdxOverflowError = WideIdx::try_from(u64::MAX).unwrap_err();
+    assert_eq!(err.to_string(), "integer out of range for index type");
+}
+
+#[test]
+fn test_fill_with_idx() {
+    let mut perm: IndexVec<Idx32, Idx32> = index_vec![Idx32::new(2), Idx32::new(0), Idx32::new(1)];
+    perm.fill_with_idx(|i| i);
+    assert_eq!(perm, [0usize, 1, 2].map(Idx32::new));
+    perm[Idx32::new(1)..].fill_with_idx(|i| i + 5);
+    assert_eq!(perm, [0usize, 5, 6].map(Idx32::new));
+}
+
+#[test]
+fn test_eq_unordered() {
+    let a: IndexVec<Idx32, u32> = index_vec![1, 2, 3];
+    let b: IndexVec<Idx32, u32> = index_vec![3, 1, 2];
+    assert!(a.eq_unordered(&b));
+    assert_ne!(a, b);
+    let c: IndexVec<Idx32, u32> = index_vec![1, 1, 2];
+    let d: IndexVec<Idx32, u32> = index_vec![1, 2, 2];
+    assert!(!c.eq_unordered(&d));
+    assert!(!a.eq_unordered(&index_vec![1, 2]));
+}
+
+index_vec::define_index_type! {
+    pub struct SentinelIdx = u16;
+    MAX_INDEX = 100;
+    SENTINEL = SentinelIdx::from_raw_unchecked(u16::MAX);
+}
+
+#[test]
+fn test_sentinel() {
+    let s = SentinelIdx::sentinel();
+    assert_eq!(s.raw(), u16::MAX);
+    assert!(s.is_sentinel());
+    assert_eq!(SentinelIdx::from_raw_unchecked(s.raw()), s);
+    assert!(SentinelIdx::from_raw_unchecked(s.raw()).is_sentinel());
+    assert!(!SentinelIdx::new(0).is_sentinel());
+    assert!(!SentinelIdx::MAX.is_sentinel());
+    // It doesn't change the max.
+    assert_eq!(SentinelIdx::MAX_INDEX, 100);
+}
+
+#[test]
+fn test_indices_and_values() {
+    let v: IndexVec<Idx32, char> = index_vec!['x', 'y', 'z'];
+    let (idxs, vals) = v.indices_and_values();
+    assert_eq!(idxs.len(), vals.len());
+    let zipped: Vec<_> = idxs.zip(vals).collect();
+    let enumerated: Vec<_> = v.iter_enumerated().collect();
+    assert_eq!(zipped, enumerated);
+    let (mut idxs, mut vals) = v.indices_and_values();
+    assert_eq!(idxs.next_back(), Some(Idx32::new(2)));
+    assert_eq!(vals.next_back(), Some(&'z'));
+}
+
+#[test]
+fn test_push_within_capacity() {
+    let mut v: IndexVec<Idx32, u32> = IndexVec::with_capacity(2);
+    let cap = v.raw.capacity();
+    for n in 0..cap {
+        assert_eq!(v.push_within_capacity(n as u32), Ok(Idx32::new(n)));
+    }
+    assert_eq!(v.push_within_capacity(99), Err(99));
+    assert_eq!(v.len(), cap);
+    assert_eq!(v.raw.capacity(), cap);
+    v.reserve(1);
+    assert_eq!(v.push_within_capacity(99), Ok(Idx32::new(cap)));
+}
+
+#[test]
+fn test_debug_sparse() {
+    let v: IndexVec<Idx16, Option<char>> = index_vec![Some('a'), None, Some('c'), None];
+    let s = format!("{:?}", v.debug_sparse(|x| x.is_none()));
+    assert_eq!(s, "{0: Some('a'), 2: Some('c')}");
+    let v: IndexVec<Idx16, u32> = index_vec![0, 0, 0];
+    assert_eq!(format!("{:?}", v.debug_sparse(|&x| x == 0)), "{}");
+}
+
+index_vec::define_index_type! {
+    pub struct SmallOffset = u8;
+    RELATIVE_ARITHMETIC = true;
+}
+
+index_vec::define_index_type! {
+    pub struct SmallOffsetUnchecked = u8;
+    RELATIVE_ARITHMETIC = true;
+    DISABLE_MAX_INDEX_CHECK = true;
+}
+
+#[test]
+fn test_relative_assign_ops() {
+    let mut offset = SmallOffset::new(10);
+    offset += SmallOffset::new(20);
+    assert_eq!(offset, SmallOffset::new(30));
+    offset -= SmallOffset::new(5);
+    assert_eq!(offset, SmallOffset::new(25));
+
+    let mut wrapped = SmallOffsetUnchecked::new(250);
+    wrapped += SmallOffsetUnchecked::new(10);
+    assert_eq!(wrapped.raw(), 4);
+}
+
+#[test]
+#[should_panic]
+fn test_relative_add_assign_overflow() {
+    let mut offset = SmallOffset::new(250);
+    offset += SmallOffset::new(10);
+}
+
+#[test]
+#[should_panic]
+fn test_relative_sub_assign_overflow() {
+    let mut offset = SmallOffset::new(1);
+    offset -= SmallOffset::new(2);
+}
+
+#[test]
+fn test_typed_len() {
+    let mut v: IndexVec<Idx32, u32> = index_vec![1, 2, 3, 4];
+    assert_eq!(v.typed_len(), Len::new(4));
+    assert_eq!(v.typed_len(), Len::of(v.next_idx()));
+    assert_eq!(Len::<Idx32>::of(Idx32::new(2)).get(), 2);
+
+    // Keep everything before index 2.
+    v.truncate_to(Len::of(Idx32::new(2)));
+    assert_eq!(v, [1, 2]);
+    v.resize_to(Len::new(3), 9);
+    assert_eq!(v, [1, 2, 9]);
+
+    let w: IndexVec<Idx32, u32> = IndexVec::with_capacity_for(v.typed_len());
+    assert!(w.raw.capacity() >= 3);
+}
+
+#[test]
+#[should_panic]
+fn test_typed_len_too_large() {
+    let _ = Len::<ZeroMax>::new(1);
+}
+
+#[test]
+fn test_unzip() {
+    let v: IndexVec<Idx32, (u32, char)> = index_vec![(1, 'a'), (2, 'b')];
+    let (nums, chars) = v.unzip();
+    assert_eq!(nums, [1, 2]);
+    assert_eq!(chars, ['a', 'b']);
+    assert_eq!(chars[Idx32::new(1)], 'b');
+}
+
+#[test]
+fn test_zip() {
+    let nums: IndexVec<Idx32, u32> = index_vec![1, 2];
+    let chars: IndexVec<Idx32, char> = index_vec!['a', 'b'];
+    let zipped = IndexVec::zip(nums.clone(), chars.clone());
+    assert_eq!(zipped, [(1, 'a'), (2, 'b')]);
+    assert_eq!(zipped.unzip(), (nums, chars));
+}
+
+#[test]
+#[should_panic(expected = "length mismatch")]
+fn test_zip_mismatch() {
+    let nums: IndexVec<Idx32, u32> = index_vec![1, 2];
+    let chars: IndexVec<Idx32, char> = index_vec!['a'];
+    let _ = IndexVec::zip(nums, chars);
+}
+
+#[cfg(target_pointer_width = "16")]
+type CfgRaw = u16;
+#[cfg(not(target_pointer_width = "16"))]
+type CfgRaw = u32;
+
+index_vec::define_index_type! {
+    #[cfg_attr(not(target_pointer_width = "16"), derive(Default))]
+    pub struct CfgIdx = CfgRaw;
+    IMPL_RAW_CONVERSIONS = true;
+}
+
+#[test]
+fn test_cfg_selected_raw() {
+    assert_eq!(
+        core::mem::size_of::<CfgIdx>(),
+        core::mem::size_of::<CfgRaw>()
+    );
+    assert_eq!(CfgIdx::MAX_INDEX, CfgRaw::MAX as usize);
+    let raw: CfgRaw = CfgIdx::new(3).raw();
+    assert_eq!(CfgIdx::from(raw), CfgIdx::new(3));
+    #[cfg(not(target_pointer_width = "16"))]
+    assert_eq!(CfgIdx::default(), CfgIdx::new(0));
+}
+
+#[test]
+fn test_nearest_by_key() {
+    fn nearest(v: &IndexSlice<Idx32, [u32]>, x: u32) -> Option<Idx32> {
+        v.nearest_by_key(&x, |&y| y.abs_diff(x))
+    }
+    let v: IndexVec<Idx32, u32> = index_vec![10, 20, 30];
+    // Exact match.
+    assert_eq!(nearest(&v, 20), Some(Idx32::new(1)));
+    // Below and above the range.
+    assert_eq!(nearest(&v, 5), Some(Idx32::new(0)));
+    assert_eq!(nearest(&v, 99), Some(Idx32::new(2)));
+    // Between two elements, picking the closer one, or the earlier on a tie.
+    assert_eq!(nearest(&v, 11), Some(Idx32::new(0)));
+    assert_eq!(nearest(&v, 19), Some(Idx32::new(1)));
+    assert_eq!(nearest(&v, 26), Some(Idx32::new(2)));
+    assert_eq!(nearest(&v, 25), Some(Idx32::new(1)));
+    let e: IndexVec<Idx32, u32> = IndexVec::new();
+    assert_eq!(nearest(&e, 1), None);
+}
+
+#[test]
+fn test_min_max_by_key_enumerated() {
+    let v: IndexVec<Idx32, i32> = index_vec![3, -7, 5, 7, -1];
+    assert_eq!(v.max_by_key_enumerated(|&x| x), Some((Idx32::new(3), &7)));
+    assert_eq!(v.min_by_key_enumerated(|&x| x), Some((Idx32::new(1), &-7)));
+    // Ties: last for max, first for min.
+    assert_eq!(
+        v.max_by_key_enumerated(|x| x.abs()),
+        Some((Idx32::new(3), &7))
+    );
+    assert_eq!(
+        v.min_by_key_enumerated(|x| x.abs() / 10),
+        Some((Idx32::new(0), &3))
+    );
+    let e: IndexVec<Idx32, i32> = IndexVec::new();
+    assert_eq!(e.max_by_key_enumerated(|&x| x), None);
+}
+
+#[test]
+fn test_all_in_bounds() {
+    let v: IndexVec<Idx32, u32> = index_vec![1, 2, 3];
+    let good = [Idx32::new(2), Idx32::new(0), Idx32::new(1)];
+    assert!(v.all_in_bounds(good.iter().copied()));
+    assert!(v.all_in_bounds(v.indices()));
+    assert!(!v.all_in_bounds(vec![Idx32::new(1), Idx32::new(3)]));
+    assert!(v.all_in_bounds(None));
+    let e: IndexVec<Idx32, u32> = IndexVec::new();
+    assert!(!e.all_in_bounds(Some(Idx32::new(0))));
+}
+
+#[cfg(target_pointer_width = "64")]
+index_vec::define_index_type! {
+    pub struct Idx64 = u64;
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn test_raw_as_wide_as_usize() {
+    // The widest raw type allowed round-trips losslessly.
+    let i = Idx64::from_raw(u64::MAX);
+    assert_eq!(i.index(), usize::MAX);
+    assert_eq!(Idx64::new(i.index()).raw(), u64::MAX);
+    assert_eq!(Idx64::MAX_INDEX, usize::MAX);
+}
+
+#[test]
+fn test_iter_enumerated_non_consuming() {
+    let mut v: IndexVec<Idx16, u32> = index_vec![1, 2, 3];
+    let first: Option<(Idx16, &u32)> = v.iter_enumerated().next();
+    assert_eq!(first, Some((Idx16::new(0), &1)));
+    for (i, x) in v.iter_mut_enumerated() {
+        *x *= 10 + i.index() as u32;
+    }
+    assert_eq!(v, [10, 22, 36]);
+
+    // Also through an `IndexSlice`, with the same index type.
+    let s: &IndexSlice<Idx16, [u32]> = &v[Idx16::new(1)..];
+    let items: Vec<(Idx16, &u32)> = s.iter_enumerated().collect();
+    assert_eq!(items, [(Idx16::new(0), &22), (Idx16::new(1), &36)]);
+}
+
+#[test]
+fn test_find_map_enumerated() {
+    let v: IndexVec<Idx32, &str> = index_vec!["a", "bb", "ccc", "dd"];
+    let found = v.find_map_enumerated(|i, s| {
+        if s.len() == 2 {
+            Some(format!("{}@{}", s, i.index()))
+        } else {
+            None
+        }
+    });
+    assert_eq!(found.as_deref(), Some("bb@1"));
+    assert_eq!(v.find_map_enumerated(|_, s| s.strip_prefix('z')), None);
+}
+
+#[test]
+fn test_indices() {
+    let v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c'];
+    let idxs = v.indices().collect::<Vec<Idx32>>();
+    assert_eq!(idxs, [Idx32::new(0), Idx32::new(1), Idx32::new(2)]);
+    assert_eq!(v.indices().len(), 3);
+    let rev: Vec<Idx32> = v.indices().rev().collect();
+    assert_eq!(rev, [Idx32::new(2), Idx32::new(1), Idx32::new(0)]);
+    let s = &v[Idx32::new(1)..];
+    assert_eq!(s.indices().len(), 2);
+    for i in s.indices() {
+        assert_eq!(s[i], v[i + 1]);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_debug_table() {
+    let ops: IndexVec<Idx16, &str> = index_vec!["load", "add", "ret"];
+    let uses: IndexVec<Idx16, u32> = index_vec![2, 1, 0];
+    let table = index_vec::debug_table::<Idx16>(
+        &[
+            ("op", &|i| ops[i].to_string()),
+            ("uses", &|i| uses[i].to_string()),
+        ],
+        ops.len(),
+    );
+    assert_eq!(
+        table,
+        "idx | op   | uses\n\
+         0   | load | 2\n\
+         1   | add  | 1\n\
+         2   | ret  | 0\n"
+    );
+}
+
+#[test]
+fn test_first_last_idx() {
+    let v: IndexVec<Idx32, u32> = index_vec![5, 6, 7];
+    assert_eq!(v.first_idx(), Idx32::new(0));
+    assert_eq!(v.last_idx(), Idx32::new(2));
+    assert_eq!(v.get_first_idx(), Some(Idx32::new(0)));
+    assert_eq!(v.get_last_idx(), Some(Idx32::new(2)));
+    let s = &v[Idx32::new(1)..];
+    assert_eq!(s.get_last_idx(), Some(Idx32::new(1)));
+
+    let e: IndexVec<Idx32, u32> = IndexVec::new();
+    assert_eq!(e.get_first_idx(), None);
+    assert_eq!(e.get_last_idx(), None);
+    assert_eq!(v[Idx32::new(3)..].get_last_idx(), None);
+}
+
+#[test]
+#[should_panic]
+fn test_first_idx_empty() {
+    let e: IndexVec<Idx32, u32> = IndexVec::new();
+    let _ = e.first_idx();
+}
+
+#[test]
+fn test_gather() {
+    let v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c'];
+    let order: IndexVec<Idx16, Idx32> = [2usize, 0, 1].iter().map(|&i| Idx32::new(i)).collect();
+    let gathered: IndexVec<Idx16, char> = v.gather(&order);
+    assert_eq!(gathered, ['c', 'a', 'b']);
+    let picked = v.gather(&order[Idx16::new(2)..]);
+    assert_eq!(picked, ['b']);
+}
+
+#[test]
+fn test_binary_search_then_insert() {
+    let mut v: IndexVec<Idx32, u32> = index_vec![10, 20, 30];
+    assert_eq!(v.binary_search(&20), Ok(Idx32::new(1)));
+    for x in [25, 5, 35] {
+        let at: Idx32 = v.binary_search(&x).unwrap_err();
+        v.insert(at, x);
+    }
+    assert_eq!(v, [5, 10, 20, 25, 30, 35]);
+
+    let at = v.binary_search_by(|probe| probe.cmp(&22)).unwrap_err();
+    assert_eq!(at, Idx32::new(3));
+    let pairs: IndexVec<Idx32, (u32, char)> = index_vec![(1, 'a'), (3, 'b'), (5, 'c')];
+    assert_eq!(
+        pairs.binary_search_by_key(&3, |&(k, _)| k),
+        Ok(Idx32::new(1))
+    );
+    assert_eq!(
+        pairs.binary_search_by_key(&4, |&(k, _)| k),
+        Err(Idx32::new(2))
+    );
+}
+
+#[test]
+fn test_scatter() {
+    let mut v: IndexVec<Idx32, char> = index_vec!['.'; 4];
+    let targets: IndexVec<Idx16, Idx32> = [3usize, 0, 3].iter().map(|&i| Idx32::new(i)).collect();
+    v.scatter(&targets, index_vec!['a', 'b', 'c']);
+    assert_eq!(v, ['b', '.', '.', 'c']);
+
+    let order: IndexVec<Idx16, Idx32> = [1usize, 2].iter().map(|&i| Idx32::new(i)).collect();
+    let gathered = v.gather(&order);
+    let mut w: IndexVec<Idx32, char> = index_vec!['.'; 4];
+    w.scatter(&order, gathered);
+    assert_eq!(w, ['.', '.', '.', '.']);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_scatter_oob() {
+    let mut v: IndexVec<Idx32, u8> = index_vec![0; 2];
+    let targets: IndexVec<Idx16, Idx32> = index_vec![Idx32::new(2)];
+    v.scatter(&targets, index_vec![1]);
+}
+
+#[test]
+fn test_index_const() {
+    use index_vec::Idx;
+    const SEVEN: Idx32 = Idx32::from_usize_unchecked(7);
+    // Resolves to the inherent `const fn`, even with `Idx` in scope.
+    const LEN: usize = SEVEN.index() + 1;
+    let arr = [0u8; LEN];
+    assert_eq!(arr.len(), 8);
+    assert_eq!(Idx::index(SEVEN), 7);
+}
+
+#[test]
+fn test_position_lookup_roundtrip() {
+    let v: IndexVec<Idx32, char> = index_vec!['x', 'y', 'z', 'y'];
+    let first = v.position(|&c| c == 'y').unwrap();
+    let last = v.rposition(|&c| c == 'y').unwrap();
+    assert_eq!((first, last), (Idx32::new(1), Idx32::new(3)));
+    assert_eq!(v[first], 'y');
+    assert_eq!(v[last], 'y');
+    assert_eq!(v.position(|&c| c == 'q'), None);
+    assert_eq!(v.rposition(|&c| c == 'q'), None);
+}
+
+#[test]
+fn test_invert() {
+    let p: IndexVec<Idx32, Idx16> = [2usize, 0, 1].iter().map(|&i| Idx16::new(i)).collect();
+    let inv: IndexVec<Idx16, Idx32> = p.invert();
+    assert_eq!(inv, [Idx32::new(1), Idx32::new(2), Idx32::new(0)]);
+    for (i, &j) in p.iter_enumerated() {
+        assert_eq!(inv[j], i);
+    }
+    assert_eq!(inv.invert(), p);
+}
+
+#[test]
+#[should_panic]
+fn test_invert_not_permutation() {
+    let p: IndexVec<Idx32, Idx16> = [1usize, 1].iter().map(|&i| Idx16::new(i)).collect();
+    let _ = p.invert();
+}
+
+#[test]
+fn test_swap_and_split_at() {
+    let mut v: IndexVec<Idx32, char> = index_vec!['a', 'b', 'c', 'd'];
+    v.swap(Idx32::new(0), Idx32::new(3));
+    assert_eq!(v, ['d', 'b', 'c', 'a']);
+
+    let (lo, hi) = v.split_at(Idx32::new(1));
+    assert_eq!(lo[Idx32::new(0)], 'd');
+    // The second half restarts at zero.
+    assert_eq!(hi[Idx32::new(0)], 'b');
+    assert_eq!(hi.last_idx(), Idx32::new(2));
+
+    let (lo, hi) = v.split_at_mut(Idx32::new(2));
+    core::mem::swap(&mut lo[Idx32::new(0)], &mut hi[Idx32::new(1)]);
+    assert_eq!(v, ['a', 'b', 'c', 'd']);
+}
+
+#[test]
+fn test_try_from_fn_n() {
+    let v: IndexVec<Idx32, usize> = IndexVec::from_fn_n(|i: Idx32| i.index() * 2, 3);
+    assert_eq!(v, [0, 2, 4]);
+
+    let ok: Result<IndexVec<Idx32, u8>, Idx32> =
+        IndexVec::try_from_fn_n(|i: Idx32| Ok(i.raw() as u8), 3);
+    assert_eq!(ok.unwrap(), [0, 1, 2]);
+
+    let mut calls = 0;
+    let err: Result<IndexVec<Idx32, u8>, Idx32> = IndexVec::try_from_fn_n(
+        |i: Idx32| {
+            calls += 1;
+            if i == 1 {
+                Err(i)
+            } else {
+                Ok(0)
+            }
+        },
+        10,
+    );
+    assert_eq!(err, Err(Idx32::new(1)));
+    assert_eq!(calls, 2);
+}
+
+#[test]
+#[should_panic]
+fn test_try_from_fn_n_too_long() {
+    let _: Result<IndexVec<SmallCheckedEarly, u8>, ()> =
+        IndexVec::try_from_fn_n(|_| Err(()), 0x100);
+}
+
+#[test]
+fn test_index_of_ref() {
+    let v: IndexVec<Idx32, u64> = index_vec![5, 6, 7];
+    let other: IndexVec<Idx32, u64> = index_vec![6];
+    let elem = v.iter().find(|&&x| x == 7).unwrap();
+    assert_eq!(v.index_of_ref(elem), Some(Idx32::new(2)));
+    assert_eq!(v.index_of_ref(&v[Idx32::new(0)]), Some(Idx32::new(0)));
+    assert_eq!(v.index_of_ref(&other[Idx32::new(0)]), None);
+    // Indices are relative to the slice being asked.
+    let tail = &v[Idx32::new(1)..];
+    assert_eq!(tail.index_of_ref(elem), Some(Idx32::new(1)));
+    assert_eq!(tail.index_of_ref(&v[Idx32::new(0)]), None);
+
+    let units: IndexVec<Idx32, ()> = index_vec![(); 3];
+    assert_eq!(units.index_of_ref(&units[Idx32::new(1)]), None);
+}
+
+#[test]
+fn test_rotate_and_copy_within() {
+    let mut v: IndexVec<Idx32, u8> = index_vec![0, 1, 2, 3, 4];
+    v.rotate_left(Idx32::new(2));
+    assert_eq!(v, [2, 3, 4, 0, 1]);
+    v.rotate_right(Idx32::new(2));
+    assert_eq!(v, [0, 1, 2, 3, 4]);
+
+    v.copy_within(Idx32::new(0)..Idx32::new(2), Idx32::new(3));
+    assert_eq!(v, [0, 1, 2, 0, 1]);
+    v.copy_within(Idx32::new(3).., Idx32::new(1));
+    assert_eq!(v, [0, 0, 1, 0, 1]);
+}
+
+#[test]
+fn test_sort_family() {
+    let base: IndexVec<Idx32, (u8, char)> = index_vec![(2, 'a'), (1, 'b'), (2, 'c'), (0, 'd')];
+
+    let mut v = base.clone();
+    v.sort();
+    assert_eq!(v, [(0, 'd'), (1, 'b'), (2, 'a'), (2, 'c')]);
+    let mut v = base.clone();
+    v.sort_by(|a, b| b.cmp(a));
+    assert_eq!(v, [(2, 'c'), (2, 'a'), (1, 'b'), (0, 'd')]);
+    let mut v = base.clone();
+    // Stable: 'a' stays ahead of 'c'.
+    v.sort_by_key(|&(k, _)| k);
+    assert_eq!(v, [(0, 'd'), (1, 'b'), (2, 'a'), (2, 'c')]);
+
+    let mut v = base.clone();
+    v.sort_unstable();
+    assert_eq!(v, [(0, 'd'), (1, 'b'), (2, 'a'), (2, 'c')]);
+    let mut v = base.clone();
+    v.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+    assert_eq!(v, [(0, 'd'), (2, 'c'), (1, 'b'), (2, 'a')]);
+    let mut v = base;
+    v.sort_unstable_by_key(|&(_, c)| c);
+    assert_eq!(v[Idx32::new(0)], (2, 'a'));
+}
+
+fn index_generic<C, R>(c: &C, r: R) -> &IndexSlice<Idx32, [u8]>
+where
+    C: core::ops::Index<R, Output = IndexSlice<Idx32, [u8]>>,
+{
+    &c[r]
+}
+
+#[test]
+fn test_index_vec_direct_index() {
+    let mut v: IndexVec<Idx32, u8> = index_vec![1, 2, 3, 4];
+    assert_eq!(v[Idx32::new(1)], 2);
+    v[Idx32::new(1)] = 20;
+    assert_eq!(&v[Idx32::new(1)..Idx32::new(3)], &[20, 3][..]);
+    v[Idx32::new(2)..][0] = 30;
+    assert_eq!(v, [1, 20, 30, 4]);
+    // Works without going through `Deref`.
+    assert_eq!(index_generic(&v, Idx32::new(2)..), &[30, 4][..]);
+}
+
+fn index_vec_range_to_strings<R: index_vec::IdxSliceRange<Idx32, u8>>(
+    v: &IndexVec<Idx32, u8>,
+    range: R,
+) -> Vec<String> {
+    v[range].iter().map(|b| b.to_string()).collect()
+}
+
+#[test]
+fn test_generic_range_bound() {
+    let v: IndexVec<Idx32, u8> = index_vec![1, 2, 3, 4];
+    assert_eq!(
+        index_vec_range_to_strings(&v, Idx32::new(1)..Idx32::new(3)),
+        ["2", "3"]
+    );
+    assert_eq!(index_vec_range_to_strings(&v, ..Idx32::new(1)), ["1"]);
+    assert_eq!(index_vec_range_to_strings(&v, ..).len(), 4);
+}
+
+#[test]
+fn test_index_box_roundtrip() {
+    let v: IndexVec<Idx32, u8> = vec![1, 2, 3].into_index_vec();
+    let b: index_vec::IndexBox<Idx32, [u8]> = v.into_boxed_slice();
+    assert_eq!(b[Idx32::new(2)], 3);
+    assert_eq!(b.last_idx(), Idx32::new(2));
+    let back: IndexVec<Idx32, u8> = b.into_vec();
+    assert_eq!(back, [1, 2, 3]);
+
+    let b = IndexSlice::<Idx16, [char]>::from_boxed_slice(vec!['x', 'y'].into_boxed_slice());
+    assert_eq!(b[Idx16::new(1)], 'y');
+    assert_eq!(b.into_vec().push('z'), Idx16::new(2));
+}
+
+#[test]
+#[should_panic(expected = "expected index 1, got 0")]
+fn test_from_enumerated_out_of_order() {
+    let pairs = vec![(Idx32::new(0), 'a'), (Idx32::new(0), 'b')];
+    let _ = IndexVec::from_enumerated(pairs);
+}
+
+#[test]
+fn test_grow_to_index() {
+    let mut v: IndexVec<Idx32, u8> = IndexVec::new();
+    v.ensure_index(Idx32::new(2));
+    assert_eq!(v, [0, 0, 0]);
+    v.ensure_index(Idx32::new(1));
+    assert_eq!(v.len(), 3);
+
+    v.grow_to(Idx32::new(4), 9);
+    assert_eq!(v, [0, 0, 0, 9, 9]);
+
+    *v.get_or_insert_with(Idx32::new(6), || 7) += 1;
+    assert_eq!(v, [0, 0, 0, 9, 9, 7, 8]);
+    *v.get_or_insert_with(Idx32::new(0), || unreachable!()) = 1;
+    assert_eq!(v[Idx32::new(0)], 1);
+
+    let mut edges: IndexVec<Idx16, Vec<Idx16>> = IndexVec::new();
+    edges
+        .get_or_insert_with(Idx16::new(3), Vec::new)
+        .push(Idx16::new(0));
+    assert_eq!(edges.len(), 4);
+    assert!(edges[Idx16::new(2)].is_empty());
+}
+
+#[test]
+fn test_map_enumerated() {
+    let v: IndexVec<Idx32, String> = index_vec!["a".to_string(), "b".to_string()];
+    let lens = v.map_collect(|i, s| (i, s.len()));
+    assert_eq!(lens, [(Idx32::new(0), 1), (Idx32::new(1), 1)]);
+
+    let own = v.map_enumerated(|i, _| i);
+    for (i, &j) in own.iter_enumerated() {
+        assert_eq!(i, j);
+    }
+    let _: &IndexVec<Idx32, Idx32> = &own;
+}
+
+#[test]
+fn test_reindexed() {
+    let v: IndexVec<Idx32, u8> = index_vec![1, 2, 3];
+    let ptr = v.as_ptr();
+    let s: &IndexSlice<Idx16, [u8]> = v.as_reindexed();
+    assert_eq!(s[Idx16::new(2)], 3);
+    let w: IndexVec<Idx16, u8> = v.into_reindexed();
+    assert_eq!(w.as_ptr(), ptr);
+    assert_eq!(w[Idx16::new(0)], 1);
+}
+
+#[test]
+#[should_panic]
+fn test_reindexed_too_long() {
+    let v: IndexVec<Idx32, u8> = index_vec![0; 0x81];
+    let _: IndexVec<SmallCheckedEarly, u8> = v.into_reindexed();
+}
+
+#[test]
+#[should_panic]
+fn test_as_reindexed_too_long() {
+    let v: IndexVec<Idx32, u8> = index_vec![0; 0x81];
+    let _: &IndexSlice<SmallCheckedEarly, [u8]> = v.as_reindexed();
+}
+
+#[test]
+fn test_try_from_usize() {
+    use index_vec::Idx;
+    fn generic<I: Idx>(i: usize) -> Option<I> {
+        I::try_from_usize(i)
+    }
+    assert_eq!(
+        generic::<SmallCheckedEarly>(0x7f),
+        Some(SmallCheckedEarly::new(0x7f))
+    );
+    assert_eq!(generic::<SmallCheckedEarly>(0x80), None);
+    assert_eq!(SmallChecked::try_from_usize(256), None);
+    assert_eq!(Idx16::try_from_usize(usize::MAX), None);
+    assert_eq!(
+        generic::<SmallUncheckedEarly>(0x80),
+        Some(SmallUncheckedEarly::from_usize_unchecked(0x80))
+    );
+    assert!(SmallUnchecked::try_from_usize(300).is_some());
+}
+
+#[test]
+fn test_checked_arith() {
+    let i = SmallCheckedEarly::new(0x7e);
+    assert_eq!(i.checked_add(1), Some(SmallCheckedEarly::new(0x7f)));
+    assert_eq!(i.checked_add(2), None);
+    assert_eq!(i.checked_add(usize::MAX), None);
+    assert_eq!(i.checked_sub(0x7e), Some(SmallCheckedEarly::new(0)));
+    assert_eq!(i.checked_sub(0x7f), None);
+
+    let u = SmallUnchecked::from_usize_unchecked(250);
+    assert_eq!(u.checked_add(5).map(|u| u.index()), Some(255));
+    assert_eq!(u.checked_add(6), None);
+    assert_eq!(Idx32::new(3).checked_sub(1), Some(Idx32::new(2)));
+}
+
+#[test]
+fn test_saturating_arith() {
+    let i = SmallCheckedEarly::new(0x7e);
+    assert_eq!(i.saturating_add(1), SmallCheckedEarly::new(0x7f));
+    assert_eq!(i.saturating_add(100), SmallCheckedEarly::new(0x7f));
+    assert_eq!(i.saturating_add(usize::MAX), SmallCheckedEarly::MAX);
+    assert_eq!(i.saturating_sub(0x7e), SmallCheckedEarly::ZERO);
+    assert_eq!(i.saturating_sub(1000), SmallCheckedEarly::ZERO);
+
+    let u = SmallChecked::new(250);
+    assert_eq!(u.saturating_add(10), SmallChecked::new(255));
+    let u = SmallUncheckedEarly::new(0x7f);
+    assert_eq!(u.saturating_add(1000).index(), 255);
+}
+
+index_vec::define_index_type! {
+    pub struct NamedIdx { pos: u16 }
+    MAX_INDEX = 100;
+    pub struct NamedPlain = u8;
+    struct NamedTwo { slot: u32 }
+}
+
+#[test]
+fn test_named_field() {
+    let i = NamedIdx::new(7);
+    assert_eq!(i.pos, 7);
+    assert_eq!(NamedIdx { pos: 3 }.index(), 3);
+    assert_eq!(i + 1, NamedIdx::new(8));
+    assert_eq!(NamedIdx::try_from_usize(101), None);
+    assert_eq!(NamedPlain::new(1).raw(), 1);
+    assert_eq!(NamedTwo::new(4).slot, 4);
+
+    let v: IndexVec<NamedIdx, char> = index_vec!['a', 'b'];
+    assert_eq!(v[NamedIdx { pos: 1 }], 'b');
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_named_field_serde() {
+    let v: IndexVec<NamedIdx, NamedIdx> = index_vec![NamedIdx { pos: 2 }, NamedIdx { pos: 0 }];
+    let json = serde_json::to_string(&v).unwrap();
+    assert_eq!(json, r#"[{"pos":2},{"pos":0}]"#);
+    let back: IndexVec<NamedIdx, NamedIdx> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, v);
+
+    // Unknown fields are ignored, but ours must be present (once), and in
+    // range.
+    let i: NamedTwo = serde_json::from_str(r#"{"other":true,"slot":4}"#).unwrap();
+    assert_eq!(i, NamedTwo { slot: 4 });
+    assert!(serde_json::from_str::<NamedTwo>("{}").is_err());
+    assert!(serde_json::from_str::<NamedTwo>(r#"{"slot":1,"slot":2}"#).is_err());
+    let err = serde_json::from_str::<NamedIdx>(r#"{"pos":101}"#).unwrap_err();
+    assert!(err.to_string().contains("exceeds MAX_INDEX"));
+    // Types without a named field are still bare integers.
+    assert_eq!(serde_json::to_string(&NamedPlain::new(3)).unwrap(), "3");
+}
+
+#[test]
+fn test_named_field_pattern() {
+    fn slot_or_zero(i: Option<NamedTwo>) -> u32 {
+        match i {
+            Some(NamedTwo { slot }) => slot,
+            None => 0,
+        }
+    }
+    assert_eq!(slot_or_zero(Some(NamedTwo { slot: 9 })), 9);
+    assert_eq!(slot_or_zero(None), 0);
+
+    let mut i = NamedIdx::new(1);
+    i.pos += 2;
+    assert_eq!(i, NamedIdx::new(3));
+    let NamedIdx { pos } = i;
+    assert_eq!(pos, 3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_index_type_serde() {
+    assert_eq!(serde_json::to_string(&Idx32::new(17)).unwrap(), "17");
+    let i: Idx32 = serde_json::from_str("17").unwrap();
+    assert_eq!(i, Idx32::new(17));
+
+    let ok: SmallCheckedEarly = serde_json::from_str("127").unwrap();
+    assert_eq!(ok, SmallCheckedEarly::MAX);
+    let err = serde_json::from_str::<SmallCheckedEarly>("128").unwrap_err();
+    assert!(err.to_string().contains("exceeds MAX_INDEX"));
+    assert!(serde_json::from_str::<SmallCheckedEarly>("-1").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_index_vec_serde_len_check() {
+    let v: IndexVec<SmallCheckedEarly, u8> = serde_json::from_str("[1,2,3]").unwrap();
+    assert_eq!(v, [1, 2, 3]);
+
+    let long = serde_json::to_string(&vec![0u8; 0x81]).unwrap();
+    // The raw type (u8) could hold this length, but `MAX_INDEX` can't.
+    let err = serde_json::from_str::<IndexVec<SmallCheckedEarly, u8>>(&long).unwrap_err();
+    assert!(err.to_string().contains("too large"));
+    let err = serde_json::from_str::<index_vec::IndexBox<SmallCheckedEarly, [u8]>>(&long);
+    assert!(err.is_err());
+    // Without the lower `MAX_INDEX`, it's fine.
+    assert!(serde_json::from_str::<IndexVec<SmallChecked, u8>>(&long).is_ok());
+}
+
+#[cfg(all(feature = "bytemuck", feature = "std"))]
+#[test]
+fn test_raw_bytes_roundtrip() {
+    let v: IndexVec<Idx32, u32> = index_vec![1, 0x0102_0304, u32::MAX];
+    assert_eq!(v.as_bytes().len(), 12);
+    assert_eq!(&v.as_bytes()[4..8], &0x0102_0304u32.to_ne_bytes());
+
+    let mut buf = Vec::new();
+    v.write_raw(&mut buf).unwrap();
+    assert_eq!(buf.len(), 8 + 12);
+    assert_eq!(&buf[..8], &3u64.to_le_bytes());
+    let back: IndexVec<Idx32, u32> = IndexVec::read_raw(&buf[..]).unwrap();
+    assert_eq!(back, v);
+
+    // Truncated input.
+    let err = IndexVec::<Idx32, u32>::read_raw(&buf[..10]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    // A header claiming far more data than follows (16 GiB here) shouldn't be
+    // allocated up front.
+    let mut huge = (u32::MAX as u64).to_le_bytes().to_vec();
+    huge.extend_from_slice(&buf[8..]);
+    let err = IndexVec::<Idx32, u32>::read_raw(&huge[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    // The length in bytes overflows `usize`.
+    let err = IndexVec::<IdxSz, u64>::read_raw(&u64::MAX.to_le_bytes()[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    // Too long for the index type.
+    let mut long = Vec::new();
+    IndexVec::<Idx32, u8>::from_vec(vec![0; 0x81])
+        .write_raw(&mut long)
+        .unwrap();
+    let err = IndexVec::<SmallCheckedEarly, u8>::read_raw(&long[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}